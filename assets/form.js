@@ -0,0 +1,120 @@
+// Review form for the manifest, served by the local web server so that the page works offline
+
+const original = JSON.parse(document.getElementById("manifest-data").textContent);
+const form = document.getElementById("review");
+const value = (id) => document.getElementById(id).value.trim();
+const checked = (id) => document.getElementById(id).checked;
+
+// Build the manifest from the controls, keeping all fields that cannot be edited
+function editedManifest() {
+  const manifest = structuredClone(original);
+  const setOrDelete = (key, value, isSet) => {
+    if (isSet) manifest[key] = value;
+    else delete manifest[key];
+  };
+
+  setOrDelete("name", value("name"), value("name") !== "");
+  setOrDelete("description", value("description"), value("description") !== "");
+  manifest.url = value("url");
+  // GitHub defaults to a private app, so `public` is only written if it was set or changed
+  setOrDelete("public", checked("public"), "public" in original || checked("public"));
+
+  if (value("webhook-url") === "") {
+    delete manifest.hook_attributes;
+  } else {
+    const hook = { ...(manifest.hook_attributes || {}), url: value("webhook-url") };
+    if (hook.active !== undefined || !checked("webhook-active")) {
+      hook.active = checked("webhook-active");
+    }
+    manifest.hook_attributes = hook;
+  }
+
+  const permissions = {};
+  for (const select of document.querySelectorAll("select.permission")) {
+    if (select.value !== "") permissions[select.dataset.permission] = select.value;
+  }
+  setOrDelete("default_permissions", permissions, Object.keys(permissions).length > 0);
+
+  const events = [...document.querySelectorAll("input.event:checked")].map(
+    (input) => input.dataset.event,
+  );
+  setOrDelete("default_events", events, events.length > 0);
+
+  return manifest;
+}
+
+function isUrl(url) {
+  try {
+    return ["http:", "https:"].includes(new URL(url).protocol);
+  } catch {
+    return false;
+  }
+}
+
+// Validate the manifest with the same rules as `github-dev-app manifest check`
+function validate(manifest) {
+  const errors = [];
+
+  if (!isUrl(manifest.url)) errors.push("The homepage must be an http or https URL.");
+  if (manifest.hook_attributes && !isUrl(manifest.hook_attributes.url)) {
+    errors.push("The webhook must be an http or https URL.");
+  }
+
+  const permissions = manifest.default_permissions || {};
+  for (const input of document.querySelectorAll("input.event:checked")) {
+    const required = input.dataset.requires.split(" ").filter((p) => p !== "");
+    if (required.length > 0 && !required.some((p) => p in permissions)) {
+      errors.push(
+        `The event ${input.dataset.event} requires the permission ${required.join(" or ")}.`,
+      );
+    }
+  }
+
+  return errors;
+}
+
+function update() {
+  const errors = validate(editedManifest());
+  const list = document.getElementById("errors");
+  list.replaceChildren(
+    ...errors.map((error) => {
+      const item = document.createElement("li");
+      item.textContent = error;
+      return item;
+    }),
+  );
+
+  document.getElementById("submit").disabled = errors.length > 0;
+  const save = document.getElementById("save");
+  if (save) save.disabled = errors.length > 0;
+
+  return errors.length === 0;
+}
+
+form.addEventListener("input", update);
+form.addEventListener("change", update);
+form.addEventListener("submit", (event) => {
+  if (!update()) {
+    event.preventDefault();
+    return;
+  }
+  document.getElementById("manifest").value = JSON.stringify(editedManifest());
+});
+
+const save = document.getElementById("save");
+if (save) {
+  save.addEventListener("click", async () => {
+    const status = document.getElementById("status");
+    if (!update()) return;
+
+    // The path is relative, since each app is served under its own path
+    const response = await fetch("manifest", {
+      method: "POST",
+      headers: { "Content-Type": "application/json" },
+      body: JSON.stringify(editedManifest()),
+    });
+    status.textContent = await response.text();
+  });
+}
+
+update();
@@ -0,0 +1,100 @@
+//! Command-line arguments for the `api` subcommand
+
+use std::path::PathBuf;
+
+use clap::Parser;
+use getset::{CopyGetters, Getters};
+use typed_builder::TypedBuilder;
+use url::Url;
+
+/// Command-line arguments for the `api` subcommand
+///
+/// The `api` subcommand sends a request to GitHub's REST or GraphQL API, authenticated either as
+/// the GitHub App or as one of its installations. Fields are sent as query parameters for `GET`
+/// requests, and as a JSON body for all other requests.
+#[derive(
+    Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Parser, CopyGetters, Getters, TypedBuilder,
+)]
+pub struct ApiArgs {
+    /// The HTTP method of the request, e.g. GET or POST
+    #[arg(required_unless_present = "graphql")]
+    #[builder(default, setter(into, strip_option))]
+    #[getset(get = "pub")]
+    method: Option<String>,
+
+    /// The path of the request, e.g. /app or /installation/repositories
+    #[arg(required_unless_present = "graphql")]
+    #[builder(default, setter(into, strip_option))]
+    #[getset(get = "pub")]
+    path: Option<String>,
+
+    /// Add a field to the request in the format key=value
+    #[arg(short = 'f', long = "field", value_parser = parse_field)]
+    #[builder(default)]
+    #[getset(get = "pub")]
+    fields: Vec<(String, String)>,
+
+    /// Authenticate as the installation with the given id instead of the app
+    #[arg(long)]
+    #[builder(default, setter(strip_option))]
+    #[getset(get_copy = "pub")]
+    installation: Option<u64>,
+
+    /// Send a GraphQL query, passed with `-f query=...`, instead of a REST request
+    #[arg(long, conflicts_with_all = ["method", "path", "paginate"])]
+    #[builder(default)]
+    #[getset(get_copy = "pub")]
+    graphql: bool,
+
+    /// Fetch all pages of a paginated resource and combine them into one response
+    #[arg(long)]
+    #[builder(default)]
+    #[getset(get_copy = "pub")]
+    paginate: bool,
+
+    /// The endpoint of the GitHub API
+    #[arg(long, default_value_t = Url::parse("https://api.github.com").unwrap())]
+    #[builder(setter(into))]
+    #[getset(get = "pub")]
+    github: Url,
+
+    /// The .env file with the app's id and private key
+    #[arg(long, default_value = ".env")]
+    #[builder(setter(into))]
+    #[getset(get = "pub")]
+    env_file: PathBuf,
+
+    /// The prefix of the variables in the .env file, e.g. `STAGING_` for `STAGING_APP_ID`
+    #[arg(long, default_value = "GITHUB_")]
+    #[builder(default = String::from("GITHUB_"), setter(into))]
+    #[getset(get = "pub")]
+    prefix: String,
+}
+
+/// Parse a field in the format key=value
+fn parse_field(field: &str) -> Result<(String, String), String> {
+    field
+        .split_once('=')
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .ok_or_else(|| format!("invalid field `{field}`, expected key=value"))
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn parse_field_splits_at_first_equals_sign() {
+        assert_eq!(
+            Ok(("query".to_string(), "a=b".to_string())),
+            parse_field("query=a=b")
+        );
+    }
+
+    #[test]
+    fn parse_field_errors_without_equals_sign() {
+        assert!(parse_field("query").is_err());
+    }
+}
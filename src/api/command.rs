@@ -0,0 +1,338 @@
+//! Command to send authenticated requests to GitHub's API
+
+use std::str::FromStr;
+
+use anyhow::{anyhow, Context, Error};
+use async_trait::async_trait;
+use reqwest::{Method, Response};
+use serde_json::{Map, Value};
+
+use crate::cli::Args;
+use crate::env::load_from_env;
+use crate::github::{app_token, installation_token, GitHubClient, HttpClient};
+use crate::Execute;
+
+use super::ApiArgs;
+
+/// Send authenticated requests to GitHub's API
+///
+/// This command sends a request to GitHub's REST or GraphQL API and prints the response as JSON.
+/// Requests are authenticated as the GitHub App, or as one of its installations, which shows
+/// exactly what the app can access.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct ApiCommand<'a> {
+    /// The arguments for the command
+    args: &'a ApiArgs,
+}
+
+impl<'a> ApiCommand<'a> {
+    /// Create a new instance of the command
+    pub fn new(args: &'a ApiArgs) -> Self {
+        Self { args }
+    }
+
+    /// Create a client that is authenticated as the app or the requested installation
+    async fn client(&self, http: &HttpClient) -> Result<GitHubClient, Error> {
+        let app = load_from_env(self.args.env_file(), self.args.prefix())?;
+        let client = GitHubClient::new(self.args.github(), http).with_token(app_token(&app)?);
+
+        match self.args.installation() {
+            Some(id) => {
+                let token = installation_token(&client, id).await?;
+                Ok(GitHubClient::new(self.args.github(), http).with_token(token))
+            }
+            None => Ok(client),
+        }
+    }
+}
+
+#[async_trait]
+impl<'a> Execute for ApiCommand<'a> {
+    async fn execute(&self, global_args: &Args) -> Result<(), Error> {
+        let client = self.client(&global_args.http_client()?).await?;
+
+        let response = if self.args.graphql() {
+            graphql(&client, self.args.fields()).await?
+        } else {
+            let method = self.args.method().as_deref().unwrap_or("GET");
+            let path = self.args.path().as_deref().unwrap_or_default();
+
+            rest(
+                &client,
+                method,
+                path,
+                self.args.fields(),
+                self.args.paginate(),
+            )
+            .await?
+        };
+
+        if let Some(response) = response {
+            println!("{}", serde_json::to_string_pretty(&response)?);
+        }
+
+        Ok(())
+    }
+}
+
+/// Send a request to the REST API
+///
+/// The fields are sent as query parameters for `GET` requests, and as a JSON object in the body of
+/// all other requests. If pagination is enabled, all pages of the resource are fetched and merged.
+async fn rest(
+    client: &GitHubClient,
+    method: &str,
+    path: &str,
+    fields: &[(String, String)],
+    paginate: bool,
+) -> Result<Option<Value>, Error> {
+    let method = Method::from_str(&method.to_uppercase())
+        .map_err(|_| anyhow!("invalid HTTP method {method}"))?;
+
+    let mut url = client.url(path)?;
+    if method == Method::GET && !fields.is_empty() {
+        url.query_pairs_mut().extend_pairs(fields);
+    }
+
+    if paginate {
+        if method != Method::GET {
+            return Err(anyhow!("pagination is only supported for GET requests"));
+        }
+
+        let pages = client.get_all_pages(url.as_str()).await?;
+        return Ok(Some(merge_pages(pages)));
+    }
+
+    let mut request = client.request(method.clone(), url.as_str())?;
+    if method != Method::GET && !fields.is_empty() {
+        request = request.json(&fields_to_object(fields));
+    }
+
+    parse_response(client.send(request).await?).await
+}
+
+/// Send a query to the GraphQL API
+///
+/// The query is passed as the field `query`. All other fields are sent as variables of the query.
+async fn graphql(
+    client: &GitHubClient,
+    fields: &[(String, String)],
+) -> Result<Option<Value>, Error> {
+    let mut variables = fields_to_object(fields);
+    let query = variables
+        .remove("query")
+        .ok_or_else(|| anyhow!("missing GraphQL query, pass it with -f query=..."))?;
+
+    let mut body = Map::new();
+    body.insert("query".into(), query);
+    body.insert("variables".into(), Value::Object(variables));
+
+    let request = client
+        .request(Method::POST, client.graphql_url().as_str())?
+        .json(&body);
+
+    parse_response(client.send(request).await?).await
+}
+
+/// Convert the fields into a JSON object
+fn fields_to_object(fields: &[(String, String)]) -> Map<String, Value> {
+    fields
+        .iter()
+        .map(|(key, value)| (key.clone(), Value::String(value.clone())))
+        .collect()
+}
+
+/// Parse the body of a response as JSON
+///
+/// Responses without a body, e.g. `204 No Content`, return `None`. Bodies that are not valid JSON
+/// are returned as a string.
+async fn parse_response(response: Response) -> Result<Option<Value>, Error> {
+    let body = response
+        .text()
+        .await
+        .context("failed to read response from GitHub")?;
+
+    if body.trim().is_empty() {
+        return Ok(None);
+    }
+
+    Ok(Some(
+        serde_json::from_str(&body).unwrap_or(Value::String(body)),
+    ))
+}
+
+/// Merge the pages of a paginated resource into a single response
+///
+/// Most resources return an array on each page, which are concatenated. Some resources wrap the
+/// array in an object, e.g. `{"total_count": 2, "repositories": [...]}`, in which case the arrays
+/// in the objects are concatenated.
+fn merge_pages(pages: Vec<Value>) -> Value {
+    let mut pages = pages.into_iter();
+    let Some(mut merged) = pages.next() else {
+        return Value::Array(Vec::new());
+    };
+
+    for page in pages {
+        match (&mut merged, page) {
+            (Value::Array(items), Value::Array(page)) => items.extend(page),
+            (Value::Object(object), Value::Object(page)) => {
+                for (key, value) in page {
+                    match (object.get_mut(&key), value) {
+                        (Some(Value::Array(items)), Value::Array(page)) => items.extend(page),
+                        (_, value) => {
+                            object.insert(key, value);
+                        }
+                    }
+                }
+            }
+            (_, page) => merged = page,
+        }
+    }
+
+    merged
+}
+
+#[cfg(test)]
+mod tests {
+    use mockito::{Matcher, Server};
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+    use url::Url;
+
+    use super::*;
+
+    fn client(url: &str) -> GitHubClient {
+        GitHubClient::new(
+            &Url::parse(url).unwrap(),
+            &HttpClient::new(None, 0).unwrap(),
+        )
+    }
+
+    fn fields(fields: &[(&str, &str)]) -> Vec<(String, String)> {
+        fields
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn rest_sends_fields_as_query_for_get() {
+        let mut server = Server::new_async().await;
+        let _mock = server
+            .mock("GET", "/app/hook/deliveries?per_page=1")
+            .with_body(r#"[{"id":1}]"#)
+            .create_async()
+            .await;
+
+        let client = client(&server.url());
+        let response = rest(
+            &client,
+            "get",
+            "/app/hook/deliveries",
+            &fields(&[("per_page", "1")]),
+            false,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(Some(json!([{"id": 1}])), response);
+    }
+
+    #[tokio::test]
+    async fn rest_sends_fields_as_body() {
+        let mut server = Server::new_async().await;
+        let _mock = server
+            .mock("PATCH", "/app/hook/config")
+            .match_body(Matcher::Json(json!({"content_type": "json"})))
+            .with_status(204)
+            .create_async()
+            .await;
+
+        let client = client(&server.url());
+        let response = rest(
+            &client,
+            "PATCH",
+            "app/hook/config",
+            &fields(&[("content_type", "json")]),
+            false,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(None, response);
+    }
+
+    #[tokio::test]
+    async fn rest_errors_on_invalid_method() {
+        let client = client("https://api.github.com");
+
+        let error = rest(&client, "GE T", "app", &[], false).await.unwrap_err();
+
+        assert_eq!("invalid HTTP method GE T", error.to_string());
+    }
+
+    #[tokio::test]
+    async fn graphql_sends_query_and_variables() {
+        let mut server = Server::new_async().await;
+        let _mock = server
+            .mock("POST", "/graphql")
+            .match_body(Matcher::Json(json!({
+                "query": "query($login: String!) { user(login: $login) { id } }",
+                "variables": { "login": "octocat" }
+            })))
+            .with_body(r#"{"data":{"user":{"id":"1"}}}"#)
+            .create_async()
+            .await;
+
+        let client = client(&server.url());
+        let response = graphql(
+            &client,
+            &fields(&[
+                (
+                    "query",
+                    "query($login: String!) { user(login: $login) { id } }",
+                ),
+                ("login", "octocat"),
+            ]),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(Some(json!({"data": {"user": {"id": "1"}}})), response);
+    }
+
+    #[test]
+    fn merge_pages_concatenates_arrays() {
+        let merged = merge_pages(vec![json!([1, 2]), json!([3])]);
+
+        assert_eq!(json!([1, 2, 3]), merged);
+    }
+
+    #[test]
+    fn merge_pages_concatenates_arrays_in_objects() {
+        let merged = merge_pages(vec![
+            json!({"total_count": 3, "repositories": [1, 2]}),
+            json!({"total_count": 3, "repositories": [3]}),
+        ]);
+
+        assert_eq!(json!({"total_count": 3, "repositories": [1, 2, 3]}), merged);
+    }
+
+    #[test]
+    fn trait_send() {
+        fn assert_send<T: Send>() {}
+        assert_send::<ApiCommand>();
+    }
+
+    #[test]
+    fn trait_sync() {
+        fn assert_sync<T: Sync>() {}
+        assert_sync::<ApiCommand>();
+    }
+
+    #[test]
+    fn trait_unpin() {
+        fn assert_unpin<T: Unpin>() {}
+        assert_unpin::<ApiCommand>();
+    }
+}
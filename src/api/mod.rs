@@ -0,0 +1,7 @@
+//! Send authenticated requests to GitHub's API as the GitHub App
+
+pub use self::args::*;
+pub use self::command::*;
+
+mod args;
+mod command;
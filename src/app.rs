@@ -2,7 +2,6 @@
 
 use getset::{CopyGetters, Getters};
 use serde::Deserialize;
-#[cfg(test)]
 use typed_builder::TypedBuilder;
 use typed_fields::{name, number, secret};
 
@@ -17,37 +16,35 @@ secret!(PrivateKey);
 ///
 /// This struct represents a GitHub App's secrets and private key. The secrets are used to
 /// authenticate the app with GitHub and the private key is used to sign requests.
-#[derive(Clone, Debug, Deserialize, CopyGetters, Getters)]
-#[cfg_attr(test, derive(TypedBuilder))]
-#[allow(unused)] // TODO Remove when the secrets are saved locally
+#[derive(Clone, Debug, Deserialize, CopyGetters, Getters, TypedBuilder)]
 pub struct App {
     /// The unique identifier for the app
-    #[cfg_attr(test, builder(setter(into)))]
+    #[builder(setter(into))]
     #[getset(get_copy = "pub")]
     id: Id,
 
     /// The unique name for the app
-    #[cfg_attr(test, builder(setter(into)))]
+    #[builder(setter(into))]
     #[getset(get = "pub")]
     name: Name,
 
     /// The client ID for the app
-    #[cfg_attr(test, builder(setter(into)))]
+    #[builder(setter(into))]
     #[getset(get = "pub")]
     client_id: ClientId,
 
     /// The client secret for the app
-    #[cfg_attr(test, builder(setter(into)))]
+    #[builder(setter(into))]
     #[getset(get = "pub")]
     client_secret: ClientSecret,
 
     /// The webhook secret for the app
-    #[cfg_attr(test, builder(setter(into, strip_option)))]
+    #[builder(default, setter(into, strip_option(fallback = webhook_secret_opt)))]
     #[getset(get = "pub")]
     webhook_secret: Option<WebhookSecret>,
 
     /// The private key for the app
-    #[cfg_attr(test, builder(setter(into)))]
+    #[builder(setter(into))]
     #[getset(get = "pub")]
     pem: PrivateKey,
 }
@@ -4,23 +4,142 @@
 //! parse command-line arguments and options. Documentation for the CLI is automatically generated
 //! from the comments on the `Args` struct.
 
-use clap::{Parser, Subcommand};
-use getset::Getters;
+use std::path::PathBuf;
 
+use anyhow::Error;
+use clap::error::ErrorKind;
+use clap::{ArgAction, CommandFactory, FromArgMatches, Parser, Subcommand};
+use getset::{CopyGetters, Getters};
+
+use crate::api::ApiArgs;
+use crate::config::Config;
+use crate::doctor::DoctorArgs;
+use crate::export_manifest::ExportManifestArgs;
+use crate::github::HttpClient;
+use crate::init::InitArgs;
+use crate::installations::InstallationsArgs;
+use crate::manifest::ManifestArgs;
+use crate::output::Output;
 use crate::register::RegisterArgs;
+use crate::tunnel::TunnelArgs;
 
 /// Create and manage a GitHub App for local development
 ///
 /// This command-line tool can be used to create and manage a GitHub App for local development. It
 /// provides a simple way to register a new GitHub App from a manifest, add the app's secrets to the
 /// .env file, and update the app when the manifest changes.
-#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Parser, Getters)]
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Parser, CopyGetters, Getters)]
 #[command(version, about)]
 pub struct Args {
     /// The command to execute
     #[command(subcommand)]
     #[getset(get = "pub")]
     command: Command,
+
+    /// Print the result of the command as a JSON object on stdout
+    ///
+    /// Messages for humans are printed to stderr instead. Errors are printed as a JSON object with
+    /// the message, the hint, and the exit code. Use `--json=false` to override the configuration.
+    #[arg(
+        long,
+        global = true,
+        action = ArgAction::Set,
+        num_args = 0..=1,
+        require_equals = true,
+        default_value = "false",
+        default_missing_value = "true"
+    )]
+    #[getset(get_copy = "pub")]
+    json: bool,
+
+    /// Print debug logs to stderr, or trace logs if given twice
+    ///
+    /// Secrets, private keys, and tokens are redacted from the logs. The `RUST_LOG` environment
+    /// variable takes precedence over this option.
+    #[arg(short, long, action = ArgAction::Count, global = true)]
+    #[getset(get_copy = "pub")]
+    verbose: u8,
+
+    /// Trust the certificates in this PEM file when connecting to GitHub
+    ///
+    /// The certificates are trusted in addition to the system's root certificates, e.g. for a
+    /// corporate proxy that intercepts TLS or a GitHub Enterprise Server with a private
+    /// certificate authority. Proxies are configured with `HTTPS_PROXY` and `NO_PROXY`.
+    #[arg(long, value_name = "PATH", env = "SSL_CERT_FILE", global = true)]
+    #[getset(get = "pub")]
+    ca_cert: Option<PathBuf>,
+
+    /// Retry requests to GitHub this many times on network and server errors
+    #[arg(long, value_name = "COUNT", default_value_t = 3, global = true)]
+    #[getset(get_copy = "pub")]
+    retries: u32,
+}
+
+impl Args {
+    /// Parse the command-line arguments with the defaults from the configuration files
+    ///
+    /// Exits the process with a usage message if the arguments are invalid, like `Args::parse`. The
+    /// apps that are listed in the configuration files are passed to the `register` command.
+    pub fn parse_with_config(config: Result<Config, Error>) -> Result<Self, Error> {
+        let (command, config) = match config
+            .and_then(|config| Ok((config.apply(<Self as CommandFactory>::command())?, config)))
+        {
+            Ok(command) => command,
+            Err(error) => return Self::parse_without_config(error),
+        };
+        let mut args =
+            Self::from_arg_matches(&command.get_matches()).unwrap_or_else(|error| error.exit());
+
+        match &mut args.command {
+            Command::Doctor(args) => {
+                args.set_config_error(config.apps().err().map(|error| format!("{error:#}")));
+            }
+            Command::Register(args) => {
+                args.set_configured_apps(config.apps()?);
+            }
+            _ => {}
+        }
+
+        Ok(args)
+    }
+
+    /// Parse the command-line arguments without the configuration files, which are invalid
+    ///
+    /// The help and the version are printed with a warning, and `doctor` reports the error as one
+    /// of its checks, so that a typo in the configuration does not hide the tools to find it. Other
+    /// commands fail with the error, since their defaults are unknown.
+    fn parse_without_config(error: Error) -> Result<Self, Error> {
+        let matches = match <Self as CommandFactory>::command().try_get_matches() {
+            Ok(matches) => matches,
+            Err(clap_error) => match clap_error.kind() {
+                ErrorKind::DisplayHelp
+                | ErrorKind::DisplayHelpOnMissingArgumentOrSubcommand
+                | ErrorKind::DisplayVersion => {
+                    eprintln!("Warning: ignoring the configuration files: {error:#}\n");
+                    clap_error.exit()
+                }
+                _ => return Err(error),
+            },
+        };
+        let mut args = Self::from_arg_matches(&matches).unwrap_or_else(|error| error.exit());
+
+        let Command::Doctor(doctor_args) = &mut args.command else {
+            return Err(error);
+        };
+        doctor_args.set_config_error(Some(format!("{error:#}")));
+
+        Ok(args)
+    }
+
+    /// Return the output of the command
+    pub fn output(&self) -> Output {
+        Output::new(self.json)
+    }
+
+    /// Create the HTTP client that is shared by all requests to GitHub
+    pub fn http_client(&self) -> Result<HttpClient, Error> {
+        HttpClient::new(self.ca_cert.as_deref(), self.retries)
+    }
 }
 
 /// Top-level commands for `github-dev-app`
@@ -29,8 +148,30 @@ pub struct Args {
 /// command has its own set of arguments and options that can be used to customize its behavior.
 #[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Subcommand)]
 pub enum Command {
+    /// Send an authenticated request to GitHub's API as the app or one of its installations
+    Api(ApiArgs),
+
+    /// Diagnose a broken local setup, e.g. a wrong private key or webhook secret
+    Doctor(DoctorArgs),
+
+    /// Export the manifest of an existing GitHub App
+    ExportManifest(ExportManifestArgs),
+
+    /// Scaffold a manifest for a new GitHub App by answering a few questions
+    Init(InitArgs),
+
+    /// Manage the installations of the GitHub App
+    #[command(subcommand_required = true)]
+    Installations(InstallationsArgs),
+
+    /// Check manifest files and print their JSON Schema
+    Manifest(ManifestArgs),
+
     /// Register a new GitHub App using a manifest file
-    Register(RegisterArgs),
+    Register(Box<RegisterArgs>),
+
+    /// Forward webhooks from a smee.io channel to a local server
+    Tunnel(TunnelArgs),
 }
 
 #[cfg(test)]
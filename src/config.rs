@@ -0,0 +1,604 @@
+//! Default options from configuration files
+//!
+//! Projects can check in a `.github-dev-app.toml` file with the options that every developer on
+//! the team uses, e.g. the path to the manifest and the port of the local web server. Developers
+//! can set their own defaults in a user-level configuration file. The options in the files are
+//! named like the command-line flags, and become the defaults of the flags:
+//!
+//! ```toml
+//! manifest = "github/app.json"
+//! port = 64001
+//!
+//! [register]
+//! org = "otterbuild"
+//! ```
+//!
+//! Options at the top level apply to every command that accepts them, while options in a table
+//! apply only to the command of the same name. The command line takes precedence over the project
+//! configuration, which takes precedence over the user configuration.
+//!
+//! Projects with several GitHub Apps list them in `[[apps]]` tables, which the `register` command
+//! registers one after another.
+
+use std::env::{current_dir, var_os};
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, bail, Context, Error};
+use clap::{Arg, ArgAction, Command, ValueHint};
+use toml::{Table, Value};
+
+use crate::error::CliError;
+use crate::register::NamedApp;
+
+/// The name of the project configuration file
+pub const PROJECT_CONFIG: &str = ".github-dev-app.toml";
+
+/// The name of the tables that list the apps of the project
+const APPS: &str = "apps";
+
+/// The configuration files that were found, ordered from lowest to highest precedence
+#[derive(Clone, Debug, Default)]
+pub struct Config {
+    /// The parsed configuration files
+    files: Vec<ConfigFile>,
+}
+
+/// A configuration file and its options
+#[derive(Clone, Debug)]
+struct ConfigFile {
+    /// The path of the file, which relative paths in the options are resolved against
+    path: PathBuf,
+
+    /// The options in the file
+    options: Table,
+}
+
+impl Config {
+    /// Load the user configuration and the project configuration
+    ///
+    /// The project configuration is searched in the current directory and its parents, so that the
+    /// tool can be run from any directory in the project. Missing files are skipped.
+    pub fn load() -> Result<Self, Error> {
+        let directory = current_dir().context("failed to read current directory")?;
+        let paths = [user_config_path(), find_project_config(&directory)];
+
+        let files = paths
+            .into_iter()
+            .flatten()
+            .map(|path| ConfigFile::read(&path))
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self { files })
+    }
+
+    /// Set the options from the configuration files as the defaults of the command's arguments
+    pub fn apply(&self, command: Command) -> Result<Command, Error> {
+        self.files
+            .iter()
+            .try_fold(command, |command, file| file.apply(command))
+    }
+
+    /// Return the apps that are listed in the configuration files
+    ///
+    /// An app in a later file replaces the app with the same name in an earlier file.
+    pub fn apps(&self) -> Result<Vec<NamedApp>, Error> {
+        let mut apps: Vec<NamedApp> = Vec::new();
+
+        for file in &self.files {
+            for app in file.apps()? {
+                match apps
+                    .iter_mut()
+                    .find(|existing| existing.name() == app.name())
+                {
+                    Some(existing) => *existing = app,
+                    None => apps.push(app),
+                }
+            }
+        }
+
+        Ok(apps)
+    }
+}
+
+impl ConfigFile {
+    /// Read and parse a configuration file
+    fn read(path: &Path) -> Result<Self, Error> {
+        let invalid = || CliError::InvalidConfig {
+            path: path.to_path_buf(),
+        };
+
+        let source = std::fs::read_to_string(path).with_context(invalid)?;
+        let options = source.parse::<Table>().with_context(invalid)?;
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            options,
+        })
+    }
+
+    /// Set the options in the file as the defaults of the command's arguments
+    ///
+    /// Options in a table are applied after the options at the top level, so that they take
+    /// precedence for their command.
+    fn apply(&self, command: Command) -> Result<Command, Error> {
+        self.apply_options(command)
+            .with_context(|| CliError::InvalidConfig {
+                path: self.path.clone(),
+            })
+    }
+
+    /// Parse the apps in the `[[apps]]` tables of the file
+    fn apps(&self) -> Result<Vec<NamedApp>, Error> {
+        let Some(apps) = self.options.get(APPS) else {
+            return Ok(Vec::new());
+        };
+
+        let directory = self.path.parent().unwrap_or(Path::new(""));
+        let apps: Vec<NamedApp> = apps
+            .clone()
+            .try_into()
+            .context("invalid list of apps")
+            .with_context(|| CliError::InvalidConfig {
+                path: self.path.clone(),
+            })?;
+
+        Ok(apps
+            .into_iter()
+            .map(|app| app.resolve_paths(directory))
+            .collect())
+    }
+
+    /// Set the options at the top level and in the tables of the commands
+    fn apply_options(&self, mut command: Command) -> Result<Command, Error> {
+        let directory = self.path.parent().unwrap_or(Path::new(""));
+
+        let mut sections = Vec::new();
+        for (name, value) in &self.options {
+            if name == APPS {
+                continue;
+            }
+
+            match value.as_table() {
+                Some(options) if command.find_subcommand(name).is_some() => {
+                    sections.push((name, options));
+                }
+                _ => command = apply_option(command, name, value, directory)?,
+            }
+        }
+
+        for (name, options) in sections {
+            command = try_mut_subcommand(command, name, |subcommand| {
+                options
+                    .iter()
+                    .try_fold(subcommand, |subcommand, (option, value)| {
+                        apply_option(subcommand, option, value, directory)
+                    })
+                    .with_context(|| format!("invalid options for command `{name}`"))
+            })?;
+        }
+
+        Ok(command)
+    }
+}
+
+/// Set an option as the default of the argument with its name in a command and its subcommands
+///
+/// Fails if the option is not accepted by any of the commands, or if its value is invalid.
+fn apply_option(
+    command: Command,
+    name: &str,
+    value: &Value,
+    directory: &Path,
+) -> Result<Command, Error> {
+    let values = to_strings(name, value)?;
+
+    let mut found = false;
+    let command = set_default(command, name, &values, directory, &mut found)?;
+
+    if !found {
+        bail!("unknown option `{name}`");
+    }
+
+    Ok(command)
+}
+
+/// Set the default of the argument with the given name in a command and its subcommands
+fn set_default(
+    mut command: Command,
+    name: &str,
+    values: &[String],
+    directory: &Path,
+    found: &mut bool,
+) -> Result<Command, Error> {
+    let arg = command
+        .get_arguments()
+        .find(|arg| arg_name(arg) == Some(name))
+        .cloned();
+
+    if let Some(arg) = arg {
+        let values = resolve_paths(&arg, values, directory);
+        validate(&command, &arg, &values)?;
+
+        command = command.mut_arg(arg.get_id().clone(), |arg| {
+            arg.default_values(values).required(false)
+        });
+        *found = true;
+    }
+
+    let subcommands = command
+        .get_subcommands()
+        .map(|subcommand| subcommand.get_name().to_string())
+        .collect::<Vec<_>>();
+
+    for subcommand in subcommands {
+        command = try_mut_subcommand(command, &subcommand, |subcommand| {
+            set_default(subcommand, name, values, directory, found)
+        })?;
+    }
+
+    Ok(command)
+}
+
+/// Modify a subcommand with a function that can fail
+fn try_mut_subcommand<F>(command: Command, name: &str, f: F) -> Result<Command, Error>
+where
+    F: FnOnce(Command) -> Result<Command, Error>,
+{
+    let mut result = Ok(());
+
+    let command = command.mut_subcommand(name, |subcommand| match f(subcommand.clone()) {
+        Ok(subcommand) => subcommand,
+        Err(error) => {
+            result = Err(error);
+            subcommand
+        }
+    });
+
+    result.map(|()| command)
+}
+
+/// Return the name of an argument in the configuration, which is the name of its flag
+fn arg_name(arg: &Arg) -> Option<&str> {
+    match arg.get_long() {
+        Some(long) => Some(long),
+        None if arg.is_positional() => Some(arg.get_id().as_str()),
+        None => None,
+    }
+}
+
+/// Convert the value of an option to the values of an argument
+fn to_strings(name: &str, value: &Value) -> Result<Vec<String>, Error> {
+    match value {
+        Value::String(string) => Ok(vec![string.clone()]),
+        Value::Integer(integer) => Ok(vec![integer.to_string()]),
+        Value::Float(float) => Ok(vec![float.to_string()]),
+        Value::Boolean(boolean) => Ok(vec![boolean.to_string()]),
+        Value::Datetime(datetime) => Ok(vec![datetime.to_string()]),
+        Value::Array(array) => array
+            .iter()
+            .map(|value| match value {
+                Value::Array(_) | Value::Table(_) => {
+                    Err(anyhow!("option `{name}` must be a list of values"))
+                }
+                value => Ok(to_strings(name, value)?.remove(0)),
+            })
+            .collect(),
+        Value::Table(_) => Err(anyhow!(
+            "option `{name}` must be a value, or the name of a command"
+        )),
+    }
+}
+
+/// Resolve relative paths against the directory of the configuration file
+fn resolve_paths(arg: &Arg, values: &[String], directory: &Path) -> Vec<String> {
+    let is_path = matches!(
+        arg.get_value_hint(),
+        ValueHint::AnyPath | ValueHint::FilePath | ValueHint::DirPath
+    );
+
+    values
+        .iter()
+        .map(|value| match is_path && Path::new(value).is_relative() {
+            true => directory.join(value).display().to_string(),
+            false => value.clone(),
+        })
+        .collect()
+}
+
+/// Check that the values are valid for the argument
+///
+/// Clap only validates defaults when they are used, and asserts that they are valid in debug
+/// builds. Invalid options are reported as errors instead.
+fn validate(command: &Command, arg: &Arg, values: &[String]) -> Result<(), Error> {
+    let name = arg_name(arg).unwrap_or_default();
+
+    if values.len() > 1 && !matches!(arg.get_action(), ArgAction::Append) {
+        bail!("option `{name}` accepts a single value");
+    }
+
+    // Parse each value with a command that only accepts the argument
+    let parser = Command::new(command.get_name().to_string()).arg(
+        Arg::new(name.to_string())
+            .value_name(name.to_string())
+            .required(true)
+            .value_parser(arg.get_value_parser().clone())
+            .allow_hyphen_values(true),
+    );
+
+    for value in values {
+        parser
+            .clone()
+            .try_get_matches_from([command.get_name(), value])
+            .map_err(|error| {
+                let message = error.to_string();
+                let message = message.lines().next().unwrap_or_default();
+
+                anyhow!("{}", message.trim_start_matches("error: "))
+            })?;
+    }
+
+    Ok(())
+}
+
+/// Return the path of the user configuration file
+///
+/// The file is `github-dev-app/config.toml` in the user's configuration directory, which is
+/// `$XDG_CONFIG_HOME` or `~/.config` on Unix, and `%APPDATA%` on Windows.
+fn user_config_path() -> Option<PathBuf> {
+    let directory = var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| var_os("APPDATA").map(PathBuf::from))
+        .or_else(|| var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+
+    let path = directory.join("github-dev-app").join("config.toml");
+    path.is_file().then_some(path)
+}
+
+/// Find the project configuration file in the directory or one of its parents
+fn find_project_config(directory: &Path) -> Option<PathBuf> {
+    directory
+        .ancestors()
+        .map(|directory| directory.join(PROJECT_CONFIG))
+        .find(|path| path.is_file())
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::{CommandFactory, FromArgMatches};
+    use pretty_assertions::assert_eq;
+    use tempfile::TempDir;
+
+    use crate::cli::{Args, Command as CliCommand};
+
+    use super::*;
+
+    fn config(path: &Path, options: &str) -> Config {
+        Config {
+            files: vec![ConfigFile {
+                path: path.to_path_buf(),
+                options: options.parse().unwrap(),
+            }],
+        }
+    }
+
+    fn parse(config: &Config, args: &[&str]) -> Result<Args, Error> {
+        let command = config.apply(<Args as CommandFactory>::command())?;
+        let matches = command.try_get_matches_from(args)?;
+
+        Ok(Args::from_arg_matches(&matches)?)
+    }
+
+    fn register_args(args: &Args) -> &crate::register::RegisterArgs {
+        match args.command() {
+            CliCommand::Register(args) => args,
+            _ => panic!("expected register command"),
+        }
+    }
+
+    #[test]
+    fn apply_sets_defaults_of_arguments() {
+        let config = config(
+            Path::new("/project/.github-dev-app.toml"),
+            r#"
+                manifest = "github/app.json"
+                port = 64001
+                json = true
+            "#,
+        );
+
+        let args = parse(&config, &["github-dev-app", "register"]).unwrap();
+
+        assert!(args.json());
+        assert_eq!(Some(64001), register_args(&args).port());
+        assert_eq!(
+            &vec![PathBuf::from("/project/github/app.json")],
+            register_args(&args).manifest()
+        );
+    }
+
+    #[test]
+    fn apply_gives_precedence_to_command_line() {
+        let config = config(
+            Path::new("/project/.github-dev-app.toml"),
+            r#"
+                manifest = "github/app.json"
+                port = 64001
+                json = true
+            "#,
+        );
+
+        let args = parse(
+            &config,
+            &[
+                "github-dev-app",
+                "register",
+                "app.json",
+                "--port",
+                "3000",
+                "--json=false",
+            ],
+        )
+        .unwrap();
+
+        assert!(!args.json());
+        assert_eq!(Some(3000), register_args(&args).port());
+        assert_eq!(
+            &vec![PathBuf::from("app.json")],
+            register_args(&args).manifest()
+        );
+    }
+
+    #[test]
+    fn apply_gives_precedence_to_tables_of_commands() {
+        let config = config(
+            Path::new("/project/.github-dev-app.toml"),
+            r#"
+                prefix = "GITHUB_"
+
+                [register]
+                prefix = "STAGING_"
+            "#,
+        );
+
+        let args = parse(&config, &["github-dev-app", "register"]).unwrap();
+
+        assert_eq!("STAGING_", register_args(&args).prefix());
+    }
+
+    #[test]
+    fn apply_gives_precedence_to_later_files() {
+        let user = config(Path::new("/home/otter/config.toml"), "port = 3000");
+        let project = config(Path::new("/project/.github-dev-app.toml"), "port = 64001");
+        let config = Config {
+            files: [user.files, project.files].concat(),
+        };
+
+        let args = parse(&config, &["github-dev-app", "register"]).unwrap();
+
+        assert_eq!(Some(64001), register_args(&args).port());
+    }
+
+    #[test]
+    fn apply_errors_on_unknown_option() {
+        let config = config(Path::new(".github-dev-app.toml"), "prot = 3000");
+
+        let error = parse(&config, &["github-dev-app", "register"]).unwrap_err();
+
+        assert!(matches!(
+            error.downcast_ref::<CliError>(),
+            Some(CliError::InvalidConfig { .. })
+        ));
+        assert_eq!(
+            "invalid configuration in .github-dev-app.toml: unknown option `prot`",
+            format!("{error:#}")
+        );
+    }
+
+    #[test]
+    fn apply_errors_on_invalid_value() {
+        let config = config(Path::new(".github-dev-app.toml"), r#"port = "otters""#);
+
+        let error = parse(&config, &["github-dev-app", "register"]).unwrap_err();
+
+        assert_eq!(
+            "invalid configuration in .github-dev-app.toml: invalid value 'otters' for '<port>': \
+             invalid digit found in string",
+            format!("{error:#}")
+        );
+    }
+
+    #[test]
+    fn apps_resolves_paths_and_replaces_apps_of_earlier_files() {
+        let user = config(
+            Path::new("/home/otter/config.toml"),
+            r#"
+                [[apps]]
+                name = "bot"
+                manifest = "bot.json"
+            "#,
+        );
+        let project = config(
+            Path::new("/project/.github-dev-app.toml"),
+            r#"
+                [[apps]]
+                name = "login"
+                env-file = "web/.env"
+
+                [[apps]]
+                name = "bot"
+                manifest = "github/bot.json"
+                prefix = "BOT_"
+            "#,
+        );
+        let config = Config {
+            files: [user.files, project.files].concat(),
+        };
+
+        let apps = config.apps().unwrap();
+
+        assert_eq!(
+            vec![
+                NamedApp::builder()
+                    .name("bot")
+                    .manifest(Some("/project/github/bot.json".into()))
+                    .prefix(Some("BOT_".into()))
+                    .build(),
+                NamedApp::builder()
+                    .name("login")
+                    .env_file(Some("/project/web/.env".into()))
+                    .build(),
+            ],
+            apps
+        );
+        assert!(parse(&config, &["github-dev-app", "register"]).is_ok());
+    }
+
+    #[test]
+    fn apps_errors_on_unknown_field() {
+        let config = config(
+            Path::new(".github-dev-app.toml"),
+            r#"
+                [[apps]]
+                name = "bot"
+                manifests = "bot.json"
+            "#,
+        );
+
+        let error = config.apps().unwrap_err();
+
+        assert!(format!("{error:#}").starts_with(
+            "invalid configuration in .github-dev-app.toml: invalid list of apps: unknown field \
+             `manifests`"
+        ));
+    }
+
+    #[test]
+    fn find_project_config_searches_parents() {
+        let directory = TempDir::new().unwrap();
+        let path = directory.path().join(PROJECT_CONFIG);
+        std::fs::write(&path, "port = 64001").unwrap();
+        std::fs::create_dir_all(directory.path().join("crates/server")).unwrap();
+
+        let found = find_project_config(&directory.path().join("crates/server"));
+
+        assert_eq!(Some(path), found);
+    }
+
+    #[test]
+    fn trait_send() {
+        fn assert_send<T: Send>() {}
+        assert_send::<Config>();
+    }
+
+    #[test]
+    fn trait_sync() {
+        fn assert_sync<T: Sync>() {}
+        assert_sync::<Config>();
+    }
+
+    #[test]
+    fn trait_unpin() {
+        fn assert_unpin<T: Unpin>() {}
+        assert_unpin::<Config>();
+    }
+}
@@ -0,0 +1,41 @@
+//! Command-line arguments for the `doctor` subcommand
+
+use std::path::PathBuf;
+
+use clap::Parser;
+use getset::{Getters, Setters};
+use typed_builder::TypedBuilder;
+use url::Url;
+
+/// Command-line arguments for the `doctor` subcommand
+///
+/// The `doctor` subcommand checks the .env file with the app's secrets, and whether GitHub accepts
+/// them. The checks that talk to GitHub authenticate as the app using the private key in the file.
+#[derive(
+    Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Parser, Getters, Setters, TypedBuilder,
+)]
+pub struct DoctorArgs {
+    /// The endpoint of the GitHub API
+    #[arg(long, default_value_t = Url::parse("https://api.github.com").unwrap())]
+    #[builder(setter(into))]
+    #[getset(get = "pub")]
+    github: Url,
+
+    /// The .env file with the app's secrets
+    #[arg(long, default_value = ".env")]
+    #[builder(default = PathBuf::from(".env"), setter(into))]
+    #[getset(get = "pub")]
+    env_file: PathBuf,
+
+    /// The prefix of the variables in the .env file, e.g. `STAGING_` for `STAGING_APP_ID`
+    #[arg(long, default_value = "GITHUB_")]
+    #[builder(default = String::from("GITHUB_"), setter(into))]
+    #[getset(get = "pub")]
+    prefix: String,
+
+    /// The error in the configuration files, which is reported as a check instead of failing
+    #[arg(skip)]
+    #[builder(default, setter(into))]
+    #[getset(get = "pub", set = "pub")]
+    config_error: Option<String>,
+}
@@ -0,0 +1,110 @@
+//! Command to diagnose a broken local setup of the GitHub App
+
+use anyhow::Error;
+use async_trait::async_trait;
+use serde_json::json;
+
+use crate::cli::Args;
+use crate::github::GitHubClient;
+use crate::output::Output;
+use crate::Execute;
+
+use super::checks::{
+    check_app, check_clock, check_config, check_env_file, check_gitignore, check_private_key,
+    check_variables, check_webhook_secret, summarize, Check, Status,
+};
+use super::DoctorArgs;
+
+/// Diagnose a broken local setup of the GitHub App
+///
+/// This command checks the configuration files, the .env file with the app's secrets, whether
+/// GitHub accepts the app's private key and webhook secret, and whether the local clock is in sync
+/// with GitHub. Every problem that is found is reported with a hint that explains how to fix it.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct DoctorCommand<'a> {
+    /// The arguments for the command
+    args: &'a DoctorArgs,
+}
+
+impl<'a> DoctorCommand<'a> {
+    /// Create a new instance of the command
+    pub fn new(args: &'a DoctorArgs) -> Self {
+        Self { args }
+    }
+}
+
+#[async_trait]
+impl<'a> Execute for DoctorCommand<'a> {
+    async fn execute(&self, global_args: &Args) -> Result<(), Error> {
+        let output = global_args.output();
+        let client = GitHubClient::new(self.args.github(), &global_args.http_client()?);
+        let env_file = self.args.env_file();
+        let prefix = self.args.prefix();
+
+        let config_check = check_config(self.args.config_error().as_deref());
+        let (env_check, variables) = check_env_file(env_file);
+        let (variables_check, app) = check_variables(variables.as_ref(), env_file, prefix);
+        let key_check = check_private_key(app.as_ref(), prefix);
+        let (app_check, app_client) = check_app(&client, app.as_ref(), &key_check, prefix).await;
+        let clock_check = check_clock(&client).await;
+        let webhook_check = check_webhook_secret(app_client.as_ref(), app.as_ref(), prefix).await;
+        let gitignore_check = check_gitignore(env_file);
+
+        let checks = [
+            config_check,
+            env_check,
+            variables_check,
+            key_check,
+            app_check,
+            clock_check,
+            webhook_check,
+            gitignore_check,
+        ];
+
+        for check in &checks {
+            print_check(check, output);
+        }
+
+        summarize(&checks)?;
+        output.result(&json!({ "checks": checks }))?;
+
+        Ok(())
+    }
+}
+
+/// Print the outcome of a check, and the hint to fix it if it failed
+fn print_check(check: &Check, output: Output) {
+    let status = match check.status {
+        Status::Ok => "ok",
+        Status::Failed => "failed",
+        Status::Skipped => "skipped",
+    };
+
+    output.message(format!("[{status}] {}: {}", check.name, check.message));
+    if let Some(hint) = &check.hint {
+        output.message(format!("  Hint: {hint}"));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn trait_send() {
+        fn assert_send<T: Send>() {}
+        assert_send::<DoctorCommand>();
+    }
+
+    #[test]
+    fn trait_sync() {
+        fn assert_sync<T: Sync>() {}
+        assert_sync::<DoctorCommand>();
+    }
+
+    #[test]
+    fn trait_unpin() {
+        fn assert_unpin<T: Unpin>() {}
+        assert_unpin::<DoctorCommand>();
+    }
+}
@@ -0,0 +1,8 @@
+//! Diagnose a broken local setup of the GitHub App
+
+pub use self::args::*;
+pub use self::command::*;
+
+mod args;
+mod checks;
+mod command;
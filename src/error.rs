@@ -0,0 +1,320 @@
+//! Errors that wrapper scripts can react to
+//!
+//! Most errors in this tool are `anyhow::Error`s with a message for the user. The errors in this
+//! module are attached to those errors as context where the cause of a failure is known. They
+//! explain how to fix the problem and determine the exit code of the process, so that scripts that
+//! run the tool can tell the failures apart.
+
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use serde::Serialize;
+use serde_json::Value;
+use thiserror::Error;
+
+/// An error with a known cause, a hint for the user, and an exit code
+#[derive(Clone, Eq, PartialEq, Debug, Error)]
+pub enum CliError {
+    /// The port of the local web server is used by another process
+    #[error("port {port} is already in use")]
+    PortInUse {
+        /// The port that was requested
+        port: u16,
+    },
+
+    /// The manifest file could not be read
+    #[error("failed to read manifest file")]
+    ManifestUnreadable,
+
+    /// The manifest could not be parsed, interpolated, or deserialized
+    #[error("{0}")]
+    InvalidManifest(String),
+
+    /// GitHub rejected the temporary code, because it has expired or has already been used
+    #[error("the temporary code from GitHub has expired or has already been used")]
+    CodeExpired,
+
+    /// The request to GitHub failed before a response was received
+    #[error("failed to send request to GitHub")]
+    Network,
+
+    /// The app's secrets could not be written to the .env file
+    #[error("failed to write {}", path.display())]
+    EnvWrite {
+        /// The path of the .env file
+        path: PathBuf,
+    },
+
+    /// GitHub did not redirect back to the local web server in time
+    #[error("timed out after {0} waiting for GitHub to redirect back, no callback was received")]
+    Timeout(String),
+
+    /// A configuration file could not be read, or contains invalid options
+    #[error("invalid configuration in {}", path.display())]
+    InvalidConfig {
+        /// The path of the configuration file
+        path: PathBuf,
+    },
+
+    /// The `doctor` command found problems with the local setup
+    #[error("{0}")]
+    Unhealthy(String),
+
+    /// The user interrupted the command
+    #[error("{0}")]
+    Interrupted(String),
+
+    /// The user interrupted the command after GitHub created the app, but before its secrets were
+    /// saved
+    #[error("{0}")]
+    SecretsNotSaved(String),
+}
+
+impl CliError {
+    /// Return a hint that explains how to fix the problem
+    pub fn hint(&self) -> String {
+        match self {
+            CliError::PortInUse { port } => format!(
+                "Stop the process that listens on port {port}, or choose another port with `--port`."
+            ),
+            CliError::ManifestUnreadable => "Check the path to the manifest, or create a new \
+                manifest with `github-dev-app init`."
+                .into(),
+            CliError::InvalidManifest(_) => "Fix the problems in the manifest. `github-dev-app \
+                manifest check` lists them with their positions in the file."
+                .into(),
+            CliError::CodeExpired => "Temporary codes expire after one hour and can only be \
+                exchanged once. Delete the app in the developer settings on GitHub and run \
+                `github-dev-app register` again."
+                .into(),
+            CliError::Network => "Check your network connection, proxy, and TLS certificates, and \
+                that the endpoint passed to `--github` is correct."
+                .into(),
+            CliError::EnvWrite { path } => format!(
+                "Check that {} and its directory are writable.",
+                path.display()
+            ),
+            CliError::Timeout(_) => "Complete the registration in the browser in time, or wait \
+                longer with `--timeout`. If you created the app on GitHub, delete it in the \
+                developer settings at https://github.com/settings/apps, or in the settings of its \
+                organization, before you register it again."
+                .into(),
+            CliError::InvalidConfig { path } => format!(
+                "Fix or remove the option in {}. Options are named like the command-line flags, \
+                 e.g. `port` for `--port`, and can be set for a single command in a table like \
+                 `[register]`.",
+                path.display()
+            ),
+            CliError::Unhealthy(_) => "Fix the problems with the hints above, and run \
+                `github-dev-app doctor` again."
+                .into(),
+            CliError::Interrupted(_) => "Run the command again to register the app.".into(),
+            CliError::SecretsNotSaved(_) => "The GitHub App was created, but its secrets were not \
+                saved. Delete the app in the developer settings on GitHub first, and then run the \
+                command again to register it, or you end up with two apps."
+                .into(),
+        }
+    }
+
+    /// Return the exit code of the process for the error
+    pub fn exit_code(&self) -> u8 {
+        match self {
+            CliError::PortInUse { .. } => 3,
+            CliError::ManifestUnreadable => 4,
+            CliError::InvalidManifest(_) => 5,
+            CliError::CodeExpired => 6,
+            CliError::Network => 7,
+            CliError::EnvWrite { .. } => 8,
+            CliError::Timeout(_) => 9,
+            CliError::InvalidConfig { .. } => 10,
+            CliError::Unhealthy(_) => 11,
+            CliError::Interrupted(_) => 130,
+            CliError::SecretsNotSaved(_) => 12,
+        }
+    }
+}
+
+/// An error with details for scripts that run the tool with `--json`
+///
+/// The details are included in the JSON object of the error, e.g. the problems that were found in
+/// a manifest. The error can be the root cause of a failure or be attached to it as context.
+#[derive(Clone, Eq, PartialEq, Debug, Error)]
+#[error("{message}")]
+pub struct Details {
+    /// The message of the error
+    pub message: String,
+
+    /// The details as a JSON value
+    pub details: Value,
+}
+
+/// An error as a JSON object for scripts that run the tool with `--json`
+#[derive(Clone, Eq, PartialEq, Debug, Serialize)]
+struct ErrorReport {
+    /// The message of the error
+    message: String,
+
+    /// The causes of the error, ending with the root cause
+    causes: Vec<String>,
+
+    /// A hint that explains how to fix the problem
+    hint: Option<String>,
+
+    /// The exit code of the process
+    exit_code: u8,
+
+    /// The details of the error, if any
+    #[serde(skip_serializing_if = "Option::is_none")]
+    details: Option<Value>,
+}
+
+impl ErrorReport {
+    /// Create the report for an error
+    fn new(error: &anyhow::Error) -> Self {
+        let cli_error = error.downcast_ref::<CliError>();
+
+        Self {
+            message: error.to_string(),
+            causes: error.chain().skip(1).map(ToString::to_string).collect(),
+            hint: cli_error.map(CliError::hint),
+            exit_code: cli_error.map(CliError::exit_code).unwrap_or(1),
+            details: error
+                .downcast_ref::<Details>()
+                .map(|details| details.details.clone()),
+        }
+    }
+}
+
+/// Print an error with its hint and return the exit code of the process
+///
+/// Errors without a known cause exit with code 1, like errors that are returned from `main`. With
+/// `--json`, the error is also printed to stdout as a JSON object.
+pub fn report(error: &anyhow::Error, json: bool) -> ExitCode {
+    let report = ErrorReport::new(error);
+
+    eprintln!("Error: {error:?}");
+    if let Some(hint) = &report.hint {
+        eprintln!("\nHint: {hint}");
+    }
+
+    if json {
+        let object = serde_json::json!({ "error": report });
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&object).unwrap_or_default()
+        );
+    }
+
+    ExitCode::from(report.exit_code)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use anyhow::{anyhow, Context};
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn exit_codes_are_distinct() {
+        let errors = [
+            CliError::PortInUse { port: 3000 },
+            CliError::ManifestUnreadable,
+            CliError::InvalidManifest("failed to deserialize manifest".into()),
+            CliError::CodeExpired,
+            CliError::Network,
+            CliError::EnvWrite {
+                path: PathBuf::from(".env"),
+            },
+            CliError::Timeout("10m".into()),
+            CliError::InvalidConfig {
+                path: PathBuf::from(".github-dev-app.toml"),
+            },
+            CliError::Unhealthy("1 of 6 checks failed: clock".into()),
+            CliError::Interrupted("interrupted".into()),
+            CliError::SecretsNotSaved("interrupted".into()),
+        ];
+
+        let exit_codes: HashSet<u8> = errors.iter().map(CliError::exit_code).collect();
+
+        assert_eq!(errors.len(), exit_codes.len());
+        assert!(!exit_codes.contains(&0) && !exit_codes.contains(&1) && !exit_codes.contains(&2));
+    }
+
+    #[test]
+    fn downcast_finds_error_in_context_chain() {
+        let error = Err::<(), _>(anyhow!("address in use"))
+            .context(CliError::PortInUse { port: 3000 })
+            .context("failed to start web server")
+            .unwrap_err();
+
+        assert_eq!(
+            Some(&CliError::PortInUse { port: 3000 }),
+            error.downcast_ref::<CliError>()
+        );
+    }
+
+    #[test]
+    fn error_report_includes_hint_and_causes() {
+        let error = Err::<(), _>(anyhow!("connection refused"))
+            .context(CliError::Network)
+            .context("failed to convert temporary code")
+            .unwrap_err();
+
+        let report = ErrorReport::new(&error);
+
+        assert_eq!(
+            ErrorReport {
+                message: "failed to convert temporary code".into(),
+                causes: vec![
+                    "failed to send request to GitHub".into(),
+                    "connection refused".into()
+                ],
+                hint: Some(CliError::Network.hint()),
+                exit_code: 7,
+                details: None,
+            },
+            report
+        );
+    }
+
+    #[test]
+    fn error_report_includes_details() {
+        let error = anyhow::Error::new(Details {
+            message: "found 1 problem(s)".into(),
+            details: serde_json::json!({ "problems": ["invalid URL"] }),
+        })
+        .context(CliError::InvalidManifest(
+            "app.json is not a valid manifest".into(),
+        ))
+        .context("failed to check manifest");
+
+        let report = ErrorReport::new(&error);
+
+        assert_eq!(5, report.exit_code);
+        assert_eq!(
+            Some(serde_json::json!({ "problems": ["invalid URL"] })),
+            report.details
+        );
+    }
+
+    #[test]
+    fn trait_send() {
+        fn assert_send<T: Send>() {}
+        assert_send::<CliError>();
+    }
+
+    #[test]
+    fn trait_sync() {
+        fn assert_sync<T: Sync>() {}
+        assert_sync::<CliError>();
+    }
+
+    #[test]
+    fn trait_unpin() {
+        fn assert_unpin<T: Unpin>() {}
+        assert_unpin::<CliError>();
+    }
+}
@@ -0,0 +1,48 @@
+//! Command-line arguments for the `export-manifest` subcommand
+
+use std::path::PathBuf;
+
+use clap::Parser;
+use getset::Getters;
+use typed_builder::TypedBuilder;
+use url::Url;
+
+/// Command-line arguments for the `export-manifest` subcommand
+///
+/// The `export-manifest` subcommand fetches the configuration of an existing GitHub App and writes
+/// it as a manifest. The app is identified either by its slug or by its id.
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Parser, Getters, TypedBuilder)]
+pub struct ExportManifestArgs {
+    /// The slug or the id of the GitHub App
+    ///
+    /// Apps can only be exported by id, and their webhook configuration can only be exported, when
+    /// the app's private key is in the .env file.
+    #[arg(long)]
+    #[builder(setter(into))]
+    #[getset(get = "pub")]
+    app: String,
+
+    /// The file to which the manifest is written, defaults to stdout
+    #[arg(short, long)]
+    #[builder(default, setter(into, strip_option))]
+    #[getset(get = "pub")]
+    output: Option<PathBuf>,
+
+    /// The endpoint of the GitHub API
+    #[arg(long, default_value_t = Url::parse("https://api.github.com").unwrap())]
+    #[builder(setter(into))]
+    #[getset(get = "pub")]
+    github: Url,
+
+    /// The .env file with the app's id and private key
+    #[arg(long, default_value = ".env")]
+    #[builder(setter(into))]
+    #[getset(get = "pub")]
+    env_file: PathBuf,
+
+    /// The prefix of the variables in the .env file, e.g. `STAGING_` for `STAGING_APP_ID`
+    #[arg(long, default_value = "GITHUB_")]
+    #[builder(default = String::from("GITHUB_"), setter(into))]
+    #[getset(get = "pub")]
+    prefix: String,
+}
@@ -0,0 +1,403 @@
+//! Command to export the manifest of an existing GitHub App
+
+use std::collections::{BTreeMap, HashMap};
+
+use anyhow::{anyhow, Context, Error};
+use async_trait::async_trait;
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::app::App;
+use crate::cli::Args;
+use crate::env::load_from_env;
+use crate::github::{app_token, GitHubClient};
+use crate::manifest::{escape_placeholders, AccessLevel, HookAttributes, Manifest, Permission};
+use crate::output::Output;
+use crate::Execute;
+
+use super::ExportManifestArgs;
+
+/// Public metadata of a GitHub App
+///
+/// This struct contains the fields of GitHub's app resource that map to a field in the manifest.
+#[derive(Clone, Eq, PartialEq, Debug, Deserialize)]
+struct AppMetadata {
+    /// The unique identifier of the app
+    id: i64,
+
+    /// The name of the app
+    name: String,
+
+    /// The description of the app
+    description: Option<String>,
+
+    /// The homepage of the app
+    external_url: Option<String>,
+
+    /// The URL of the app on GitHub
+    html_url: String,
+
+    /// The permissions that the app requests
+    #[serde(default)]
+    permissions: HashMap<String, String>,
+
+    /// The events to which the app subscribes
+    #[serde(default)]
+    events: Vec<String>,
+}
+
+/// Webhook configuration of a GitHub App
+#[derive(Clone, Eq, PartialEq, Debug, Deserialize)]
+struct HookConfig {
+    /// The URL to which the webhooks are delivered
+    url: Option<String>,
+}
+
+/// Export the manifest of an existing GitHub App
+///
+/// This command fetches the public metadata, permissions, and events of a GitHub App and writes
+/// them as a manifest. The webhook configuration is private, and is only included if the app's
+/// private key is in the .env file.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct ExportManifestCommand<'a> {
+    /// The arguments for the command
+    args: &'a ExportManifestArgs,
+}
+
+impl<'a> ExportManifestCommand<'a> {
+    /// Create a new instance of the command
+    pub fn new(args: &'a ExportManifestArgs) -> Self {
+        Self { args }
+    }
+}
+
+#[async_trait]
+impl<'a> Execute for ExportManifestCommand<'a> {
+    async fn execute(&self, global_args: &Args) -> Result<(), Error> {
+        // The manifest is printed to stdout unless it is written to a file
+        let output = match self.args.output() {
+            Some(_) => global_args.output(),
+            None => global_args.output().reserve_stdout(),
+        };
+
+        // The credentials are optional when the app is exported by its slug
+        let credentials = load_from_env(self.args.env_file(), self.args.prefix());
+
+        let client = GitHubClient::new(self.args.github(), &global_args.http_client()?);
+        let manifest =
+            export_manifest(&client, self.args.app(), credentials.as_ref(), output).await?;
+        let json = format!("{}\n", serde_json::to_string_pretty(&manifest)?);
+
+        match self.args.output() {
+            Some(path) => {
+                std::fs::write(path, json)
+                    .with_context(|| format!("failed to write manifest to {}", path.display()))?;
+                output.message(format!("Exported manifest to {}", path.display()));
+                output.result(&json!({ "manifest": path }))?;
+            }
+            None => print!("{json}"),
+        }
+
+        Ok(())
+    }
+}
+
+/// Export the manifest of the given app
+///
+/// Apps that are identified by their slug are fetched from the public API. Apps that are identified
+/// by their id can only be fetched with the app's own credentials, which are also required to read
+/// the webhook configuration.
+async fn export_manifest(
+    client: &GitHubClient,
+    app: &str,
+    credentials: Result<&App, &Error>,
+    output: Output,
+) -> Result<Manifest, Error> {
+    let metadata: AppMetadata = match app.parse::<i64>() {
+        Ok(id) => {
+            let credentials = credentials.map_err(|error| {
+                anyhow!("apps can only be exported by id with their credentials: {error:#}")
+            })?;
+
+            if credentials.id().get() != id {
+                return Err(anyhow!(
+                    "the .env file contains the credentials of app {}, not of app {id}",
+                    credentials.id()
+                ));
+            }
+
+            authenticated_client(client, credentials)?
+                .get("app")
+                .await
+                .context("failed to fetch app")?
+        }
+        Err(_) => client
+            .get(&format!("apps/{app}"))
+            .await
+            .with_context(|| format!("failed to fetch app {app}"))?,
+    };
+
+    let hook_config = match credentials {
+        Ok(credentials) if credentials.id().get() == metadata.id => Some(
+            authenticated_client(client, credentials)?
+                .get::<HookConfig>("app/hook/config")
+                .await
+                .context("failed to fetch webhook configuration")?,
+        ),
+        _ => {
+            output.message(
+                "Skipping the webhook configuration, which requires the app's credentials",
+            );
+            None
+        }
+    };
+
+    Ok(metadata_to_manifest(metadata, hook_config, output))
+}
+
+/// Create a client that is authenticated as the app
+fn authenticated_client(client: &GitHubClient, credentials: &App) -> Result<GitHubClient, Error> {
+    Ok(client.clone().with_token(app_token(credentials)?))
+}
+
+/// Convert the app's metadata into a manifest
+///
+/// The name and the description are escaped, so that text like `{{...}}` is not mistaken for a
+/// placeholder when the manifest is loaded.
+fn metadata_to_manifest(
+    metadata: AppMetadata,
+    hook_config: Option<HookConfig>,
+    output: Output,
+) -> Manifest {
+    let url = metadata.external_url.unwrap_or(metadata.html_url);
+    let mut manifest = Manifest::builder().url(url.into()).build();
+
+    manifest.set_name(Some(escape_placeholders(&metadata.name).into()));
+    manifest.set_description(
+        metadata
+            .description
+            .filter(|description| !description.is_empty())
+            .map(|description| escape_placeholders(&description).into()),
+    );
+
+    if let Some(url) = hook_config
+        .and_then(|hook_config| hook_config.url)
+        .filter(|url| !url.is_empty())
+    {
+        manifest.set_hook_attributes(Some(HookAttributes::builder().url(url).build()));
+    }
+
+    if !metadata.events.is_empty() {
+        manifest.set_default_events(Some(metadata.events.into_iter().map(Into::into).collect()));
+    }
+
+    if !metadata.permissions.is_empty() {
+        manifest.set_default_permissions(Some(convert_permissions(metadata.permissions, output)));
+    }
+
+    manifest
+}
+
+/// Convert the app's permissions into the permissions of the manifest
+///
+/// GitHub might return permissions that are not in the catalog yet. These are skipped with a
+/// warning, since they would make the manifest invalid.
+fn convert_permissions(
+    permissions: HashMap<String, String>,
+    output: Output,
+) -> BTreeMap<Permission, AccessLevel> {
+    permissions
+        .into_iter()
+        .filter_map(
+            |(permission, access)| match (permission.parse(), access.parse()) {
+                (Ok(permission), Ok(access)) => Some((permission, access)),
+                _ => {
+                    output.message(format!(
+                        "Skipping unknown permission {permission}: {access}"
+                    ));
+                    None
+                }
+            },
+        )
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+    use mockito::{Matcher, Server};
+    use pretty_assertions::assert_eq;
+    use url::Url;
+
+    use crate::github::HttpClient;
+    use crate::manifest::{Event, Variables};
+
+    use super::*;
+
+    const PRIVATE_KEY: &str = include_str!("../../tests/fixtures/private-key.pem");
+
+    const METADATA: &str = indoc! {r#"
+        {
+            "id": 1,
+            "slug": "octoapp",
+            "name": "Octoapp",
+            "description": "",
+            "external_url": "https://example.com",
+            "html_url": "https://github.com/apps/octoapp",
+            "permissions": { "issues": "write", "metadata": "read" },
+            "events": ["issues"]
+        }
+    "#};
+
+    fn client(url: &str) -> GitHubClient {
+        GitHubClient::new(
+            &Url::parse(url).unwrap(),
+            &HttpClient::new(None, 0).unwrap(),
+        )
+    }
+
+    fn credentials(id: i64) -> App {
+        App::builder()
+            .id(id)
+            .name("octoapp")
+            .client_id("client_id")
+            .client_secret("client_secret")
+            .pem(PRIVATE_KEY)
+            .build()
+    }
+
+    #[tokio::test]
+    async fn export_manifest_by_slug_without_credentials() {
+        let mut server = Server::new_async().await;
+        let _mock = server
+            .mock("GET", "/apps/octoapp")
+            .with_body(METADATA)
+            .create_async()
+            .await;
+        let error = anyhow!("missing .env file");
+
+        let manifest = export_manifest(
+            &client(&server.url()),
+            "octoapp",
+            Err(&error),
+            Output::default(),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(&Some("Octoapp".into()), manifest.name());
+        assert_eq!(&None, manifest.description());
+        assert_eq!(&None, manifest.hook_attributes());
+        assert_eq!(&Some(vec![Event::Issues]), manifest.default_events());
+    }
+
+    #[tokio::test]
+    async fn export_manifest_by_id_includes_webhook() {
+        let mut server = Server::new_async().await;
+        let _app = server
+            .mock("GET", "/app")
+            .match_header("authorization", Matcher::Regex("^Bearer ey".into()))
+            .with_body(METADATA)
+            .create_async()
+            .await;
+        let _hook = server
+            .mock("GET", "/app/hook/config")
+            .with_body(r#"{"content_type":"json","url":"https://smee.io/otters"}"#)
+            .create_async()
+            .await;
+
+        let manifest = export_manifest(
+            &client(&server.url()),
+            "1",
+            Ok(&credentials(1)),
+            Output::default(),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            "https://smee.io/otters",
+            manifest.hook_attributes().as_ref().unwrap().url().get()
+        );
+    }
+
+    #[tokio::test]
+    async fn export_manifest_by_id_errors_on_other_app() {
+        let error = export_manifest(
+            &client("https://api.github.com"),
+            "2",
+            Ok(&credentials(1)),
+            Output::default(),
+        )
+        .await
+        .unwrap_err();
+
+        assert_eq!(
+            "the .env file contains the credentials of app 1, not of app 2",
+            error.to_string()
+        );
+    }
+
+    #[test]
+    fn convert_permissions_skips_unknown_permissions() {
+        let permissions = HashMap::from([
+            ("issues".to_string(), "write".to_string()),
+            ("future_permission".to_string(), "read".to_string()),
+        ]);
+
+        let permissions = convert_permissions(permissions, Output::default());
+
+        assert_eq!(
+            BTreeMap::from([(Permission::Issues, AccessLevel::Write)]),
+            permissions
+        );
+    }
+
+    #[test]
+    fn metadata_to_manifest_round_trips() {
+        let metadata: AppMetadata = serde_json::from_str(METADATA).unwrap();
+
+        let manifest = metadata_to_manifest(metadata, None, Output::default());
+        let json = serde_json::to_value(&manifest).unwrap();
+
+        assert_eq!(
+            manifest,
+            Manifest::from_value(json, &Variables::default()).unwrap()
+        );
+    }
+
+    #[test]
+    fn metadata_to_manifest_escapes_placeholders() {
+        let mut metadata: AppMetadata = serde_json::from_str(METADATA).unwrap();
+        metadata.name = "Octoapp {{env}}".into();
+        metadata.description = Some("Replies with {{ greeting }} to ${{ matrix.os }}".into());
+
+        let manifest = metadata_to_manifest(metadata, None, Output::default());
+        let json = serde_json::to_value(&manifest).unwrap();
+        let loaded = Manifest::from_value(json, &Variables::default()).unwrap();
+
+        assert_eq!(&Some("Octoapp {{env}}".into()), loaded.name());
+        assert_eq!(
+            &Some("Replies with {{ greeting }} to ${{ matrix.os }}".into()),
+            loaded.description()
+        );
+    }
+
+    #[test]
+    fn trait_send() {
+        fn assert_send<T: Send>() {}
+        assert_send::<ExportManifestCommand>();
+    }
+
+    #[test]
+    fn trait_sync() {
+        fn assert_sync<T: Sync>() {}
+        assert_sync::<ExportManifestCommand>();
+    }
+
+    #[test]
+    fn trait_unpin() {
+        fn assert_unpin<T: Unpin>() {}
+        assert_unpin::<ExportManifestCommand>();
+    }
+}
@@ -0,0 +1,7 @@
+//! Export the manifest of an existing GitHub App
+
+pub use self::args::*;
+pub use self::command::*;
+
+mod args;
+mod command;
@@ -0,0 +1,399 @@
+//! HTTP client for GitHub's REST API
+
+use anyhow::{bail, Context, Error};
+use reqwest::header::{HeaderMap, ACCEPT, AUTHORIZATION, LINK, USER_AGENT};
+use reqwest::{Method, RequestBuilder, Response, StatusCode};
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use tracing::{debug, trace};
+use url::Url;
+
+use crate::error::CliError;
+
+use super::{HttpClient, Token};
+
+/// The user agent that identifies the requests of this tool
+const USER_AGENT_VALUE: &str = "otterbuild/github-dev-app";
+
+/// The version of GitHub's REST API that is requested
+const API_VERSION: &str = "2022-11-28";
+
+/// An unsuccessful response from GitHub
+///
+/// The error includes the message that GitHub returned in the body of the response. Callers can
+/// inspect the status code to handle specific errors, e.g. resources that were not found.
+#[derive(Clone, Eq, PartialEq, Debug, thiserror::Error)]
+#[error("GitHub responded with {status}: {message}")]
+pub struct ResponseError {
+    /// The status code of the response
+    pub status: StatusCode,
+
+    /// The message returned by GitHub, or the body of the response
+    pub message: String,
+}
+
+/// HTTP client for GitHub's REST API
+///
+/// The client sends requests to the configured endpoint of the GitHub API, which can either be
+/// GitHub.com or a GitHub Enterprise Server. Every request identifies this tool with its user agent
+/// and is optionally authenticated with a token.
+#[derive(Clone, Debug)]
+pub struct GitHubClient {
+    /// The endpoint of the GitHub API, always ending with a slash
+    endpoint: Url,
+
+    /// The token that is used to authenticate requests
+    token: Option<Token>,
+
+    /// The shared HTTP client
+    http: HttpClient,
+}
+
+impl GitHubClient {
+    /// Create a new client for the given endpoint that sends requests with the shared HTTP client
+    ///
+    /// Endpoints of GitHub Enterprise Server include a path, e.g. `https://example.com/api/v3`. A
+    /// trailing slash is appended to the endpoint so that paths are joined after the last segment
+    /// instead of replacing it.
+    pub fn new(endpoint: &Url, http: &HttpClient) -> Self {
+        let mut endpoint = endpoint.clone();
+        if !endpoint.path().ends_with('/') {
+            endpoint.set_path(&format!("{}/", endpoint.path()));
+        }
+
+        Self {
+            endpoint,
+            token: None,
+            http: http.clone(),
+        }
+    }
+
+    /// Authenticate all requests with the given token
+    pub fn with_token(mut self, token: Token) -> Self {
+        self.token = Some(token);
+        self
+    }
+
+    /// Return the endpoint of the GitHub API
+    pub fn endpoint(&self) -> &Url {
+        &self.endpoint
+    }
+
+    /// Return the shared HTTP client
+    pub fn http(&self) -> &HttpClient {
+        &self.http
+    }
+
+    /// Resolve a path against the endpoint
+    ///
+    /// Absolute URLs are returned unchanged, which is used to follow the links that GitHub returns
+    /// for pagination. Since requests carry the token of the client, absolute URLs must have the
+    /// same origin as the endpoint.
+    pub fn url(&self, path: &str) -> Result<Url, Error> {
+        if let Ok(url) = Url::parse(path) {
+            if url.origin() != self.endpoint.origin() {
+                bail!(
+                    "refusing to send request to {}, which is not on the GitHub API at {}",
+                    url.origin().ascii_serialization(),
+                    self.endpoint.origin().ascii_serialization()
+                );
+            }
+
+            return Ok(url);
+        }
+
+        self.endpoint
+            .join(path.trim_start_matches('/'))
+            .with_context(|| format!("failed to build URL for path {path}"))
+    }
+
+    /// Return the URL of the GraphQL API
+    ///
+    /// GitHub.com serves the GraphQL API at `/graphql`. GitHub Enterprise Server serves the REST
+    /// API at `/api/v3` and the GraphQL API at `/api/graphql`.
+    pub fn graphql_url(&self) -> Url {
+        let path = match self.endpoint.path().strip_suffix("/v3/") {
+            Some(prefix) => format!("{prefix}/graphql"),
+            None => format!("{}graphql", self.endpoint.path()),
+        };
+
+        let mut url = self.endpoint.clone();
+        url.set_path(&path);
+
+        url
+    }
+
+    /// Create a request to the given path
+    ///
+    /// The request carries the headers that GitHub expects from API clients, and the token if the
+    /// client is authenticated.
+    pub fn request(&self, method: Method, path: &str) -> Result<RequestBuilder, Error> {
+        let mut request = self
+            .http
+            .client()
+            .request(method, self.url(path)?)
+            .header(ACCEPT, "application/vnd.github+json")
+            .header(USER_AGENT, USER_AGENT_VALUE)
+            .header("X-GitHub-Api-Version", API_VERSION);
+
+        if let Some(token) = &self.token {
+            request = request.header(AUTHORIZATION, format!("Bearer {}", token.expose()));
+        }
+
+        Ok(request)
+    }
+
+    /// Send a request and check its status
+    ///
+    /// Responses with an unsuccessful status code are turned into an error that includes the
+    /// message returned by GitHub. Requests that fail due to a temporary problem are retried.
+    /// Requests and responses are logged, and the logger redacts the token in the `Authorization`
+    /// header.
+    pub async fn send(&self, request: RequestBuilder) -> Result<Response, Error> {
+        let request = request.build().context("failed to build request")?;
+        debug!(method = %request.method(), url = %request.url(), "sending request to GitHub");
+        trace!(headers = ?request.headers(), "request headers");
+
+        let response = self
+            .http
+            .execute(request)
+            .await
+            .context(CliError::Network)?;
+
+        let status = response.status();
+        debug!(status = %status, url = %response.url(), "received response from GitHub");
+        trace!(headers = ?response.headers(), "response headers");
+
+        if status.is_success() {
+            return Ok(response);
+        }
+
+        let body = response.text().await.unwrap_or_default();
+        debug!(body = %body, "GitHub returned an error");
+        let message = serde_json::from_str::<Value>(&body)
+            .ok()
+            .and_then(|json| json["message"].as_str().map(String::from))
+            .unwrap_or(body);
+
+        Err(ResponseError { status, message }.into())
+    }
+
+    /// Send a `GET` request and deserialize the response
+    pub async fn get<T: DeserializeOwned>(&self, path: &str) -> Result<T, Error> {
+        self.send(self.request(Method::GET, path)?)
+            .await?
+            .json()
+            .await
+            .context("failed to parse response from GitHub")
+    }
+
+    /// Send `GET` requests for all pages of a paginated resource
+    ///
+    /// GitHub links to the next page of a resource in the `Link` header. This method follows the
+    /// links until the last page has been fetched, and returns the body of every page.
+    pub async fn get_all_pages(&self, path: &str) -> Result<Vec<Value>, Error> {
+        let mut pages = Vec::new();
+        let mut next = Some(path.to_string());
+
+        while let Some(path) = next {
+            let response = self.send(self.request(Method::GET, &path)?).await?;
+
+            next = next_page(response.headers());
+            pages.push(
+                response
+                    .json()
+                    .await
+                    .context("failed to parse response from GitHub")?,
+            );
+        }
+
+        Ok(pages)
+    }
+}
+
+/// Find the link to the next page in the response headers
+///
+/// The `Link` header has the format `<https://api.github.com/...?page=2>; rel="next", <...>;
+/// rel="last"`.
+fn next_page(headers: &HeaderMap) -> Option<String> {
+    let link = headers.get(LINK)?.to_str().ok()?;
+
+    link.split(',').find_map(|part| {
+        let (url, params) = part.split_once(';')?;
+        let is_next = params
+            .split(';')
+            .any(|param| param.trim() == r#"rel="next""#);
+
+        is_next.then(|| {
+            url.trim()
+                .trim_start_matches('<')
+                .trim_end_matches('>')
+                .to_string()
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use mockito::Server;
+    use pretty_assertions::assert_eq;
+    use reqwest::header::HeaderValue;
+
+    use super::*;
+
+    fn http() -> HttpClient {
+        HttpClient::new(None, 0).unwrap()
+    }
+
+    #[test]
+    fn url_joins_path_to_enterprise_endpoint() {
+        let client = GitHubClient::new(
+            &Url::parse("https://github.example.com/api/v3").unwrap(),
+            &http(),
+        );
+
+        let url = client.url("/app/installations").unwrap();
+
+        assert_eq!(
+            "https://github.example.com/api/v3/app/installations",
+            url.as_str()
+        );
+    }
+
+    #[test]
+    fn url_keeps_absolute_urls() {
+        let client = GitHubClient::new(&Url::parse("https://api.github.com").unwrap(), &http());
+
+        let url = client.url("https://api.github.com/app?page=2").unwrap();
+
+        assert_eq!("https://api.github.com/app?page=2", url.as_str());
+    }
+
+    #[test]
+    fn url_rejects_absolute_urls_on_other_origins() {
+        let client = GitHubClient::new(&Url::parse("https://api.github.com").unwrap(), &http());
+
+        let error = client.url("https://example.com/app").unwrap_err();
+
+        assert_eq!(
+            "refusing to send request to https://example.com, which is not on the GitHub API at \
+             https://api.github.com",
+            error.to_string()
+        );
+    }
+
+    #[tokio::test]
+    async fn get_all_pages_rejects_links_to_other_origins() {
+        let mut server = Server::new_async().await;
+        let _mock = server
+            .mock("GET", "/items")
+            .with_header("link", r#"<https://example.com/items/2>; rel="next""#)
+            .with_body("[1]")
+            .create_async()
+            .await;
+
+        let client = GitHubClient::new(&Url::parse(&server.url()).unwrap(), &http());
+        let error = client.get_all_pages("items").await.unwrap_err();
+
+        assert!(error.to_string().starts_with("refusing to send request"));
+    }
+
+    #[test]
+    fn graphql_url_for_github_com() {
+        let client = GitHubClient::new(&Url::parse("https://api.github.com").unwrap(), &http());
+
+        let url = client.graphql_url();
+
+        assert_eq!("https://api.github.com/graphql", url.as_str());
+    }
+
+    #[test]
+    fn graphql_url_for_enterprise_server() {
+        let client = GitHubClient::new(
+            &Url::parse("https://github.example.com/api/v3").unwrap(),
+            &http(),
+        );
+
+        let url = client.graphql_url();
+
+        assert_eq!("https://github.example.com/api/graphql", url.as_str());
+    }
+
+    #[test]
+    fn next_page_finds_next_link() {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            LINK,
+            HeaderValue::from_static(
+                r#"<https://api.github.com/x?page=1>; rel="prev", <https://api.github.com/x?page=3>; rel="next""#,
+            ),
+        );
+
+        assert_eq!(
+            Some("https://api.github.com/x?page=3".to_string()),
+            next_page(&headers)
+        );
+    }
+
+    #[tokio::test]
+    async fn get_all_pages_follows_links() {
+        let mut server = Server::new_async().await;
+        let url = server.url();
+        let first = server
+            .mock("GET", "/items")
+            .with_header("link", &format!(r#"<{url}/items/2>; rel="next""#))
+            .with_body("[1]")
+            .create_async()
+            .await;
+        let second = server
+            .mock("GET", "/items/2")
+            .with_body("[2]")
+            .create_async()
+            .await;
+
+        let client = GitHubClient::new(&Url::parse(&url).unwrap(), &http());
+        let pages = client.get_all_pages("items").await.unwrap();
+
+        first.assert_async().await;
+        second.assert_async().await;
+        assert_eq!(vec![serde_json::json!([1]), serde_json::json!([2])], pages);
+    }
+
+    #[tokio::test]
+    async fn send_errors_with_github_message() {
+        let mut server = Server::new_async().await;
+        let _mock = server
+            .mock("GET", "/app")
+            .with_status(401)
+            .with_body(r#"{"message":"Bad credentials"}"#)
+            .create_async()
+            .await;
+
+        let client = GitHubClient::new(&Url::parse(&server.url()).unwrap(), &http());
+        let request = client.request(Method::GET, "app").unwrap();
+        let error = client.send(request).await.unwrap_err();
+
+        assert_eq!(
+            "GitHub responded with 401 Unauthorized: Bad credentials",
+            error.to_string()
+        );
+    }
+
+    #[test]
+    fn trait_send() {
+        fn assert_send<T: Send>() {}
+        assert_send::<GitHubClient>();
+    }
+
+    #[test]
+    fn trait_sync() {
+        fn assert_sync<T: Sync>() {}
+        assert_sync::<GitHubClient>();
+    }
+
+    #[test]
+    fn trait_unpin() {
+        fn assert_unpin<T: Unpin>() {}
+        assert_unpin::<GitHubClient>();
+    }
+}
@@ -0,0 +1,242 @@
+//! Shared HTTP client for requests to GitHub
+//!
+//! Every request to GitHub is sent through the same client, which is configured once from the
+//! global options of the command line. The client retries requests that failed due to a temporary
+//! problem, respects the proxy settings in the environment, and trusts additional root certificates
+//! for corporate proxies and GitHub Enterprise Servers with a private certificate authority.
+
+use std::path::Path;
+use std::time::Duration;
+
+use anyhow::{Context, Error};
+use reqwest::{Certificate, Client, Method, Request, Response};
+use tokio::time::sleep;
+use tracing::warn;
+
+/// The time to wait for a connection to GitHub
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The time to wait for a complete response from GitHub
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// The time to wait before the first retry, which doubles with every further attempt
+const INITIAL_BACKOFF: Duration = Duration::from_millis(500);
+
+/// The longest time to wait before a retry
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+/// HTTP client that retries requests with an exponential backoff
+///
+/// Requests are retried when the connection to GitHub fails. Idempotent requests are also retried
+/// when GitHub responds with a server error, since other requests like the conversion of a
+/// temporary code might have succeeded on GitHub's side. Proxies are configured with the
+/// `HTTPS_PROXY`, `HTTP_PROXY`, `ALL_PROXY`, and `NO_PROXY` environment variables, which `reqwest`
+/// reads when the client is built.
+#[derive(Clone, Debug)]
+pub struct HttpClient {
+    /// The underlying HTTP client
+    client: Client,
+
+    /// The number of times that a failed request is retried
+    retries: u32,
+}
+
+impl HttpClient {
+    /// Create a new client
+    ///
+    /// The certificates in the given PEM file are trusted in addition to the system's root
+    /// certificates. The file can contain a single certificate or a bundle of certificates.
+    pub fn new(ca_cert: Option<&Path>, retries: u32) -> Result<Self, Error> {
+        let mut builder = Client::builder()
+            .connect_timeout(CONNECT_TIMEOUT)
+            .timeout(REQUEST_TIMEOUT);
+
+        if let Some(path) = ca_cert {
+            let pem = std::fs::read(path).with_context(|| {
+                format!("failed to read CA certificate from {}", path.display())
+            })?;
+            let certificates = Certificate::from_pem_bundle(&pem)
+                .with_context(|| format!("failed to parse CA certificate in {}", path.display()))?;
+
+            for certificate in certificates {
+                builder = builder.add_root_certificate(certificate);
+            }
+        }
+
+        let client = builder.build().context("failed to build HTTP client")?;
+
+        Ok(Self { client, retries })
+    }
+
+    /// Return the underlying HTTP client, which is used to build requests
+    pub fn client(&self) -> &Client {
+        &self.client
+    }
+
+    /// Send a request, and retry it if it fails due to a temporary problem
+    ///
+    /// Requests with a streaming body cannot be cloned, and are therefore sent only once.
+    pub async fn execute(&self, mut request: Request) -> Result<Response, reqwest::Error> {
+        let mut attempt = 0;
+
+        let method = request.method().clone();
+
+        loop {
+            let retry = request.try_clone();
+            let result = self.client.execute(request).await;
+
+            let reason = match &result {
+                Ok(response) if response.status().is_server_error() && is_idempotent(&method) => {
+                    format!("GitHub responded with {}", response.status())
+                }
+                Err(error) if error.is_connect() => format!("{error}"),
+                _ => return result,
+            };
+
+            match retry {
+                Some(retry) if attempt < self.retries => {
+                    let backoff = backoff(attempt);
+                    attempt += 1;
+                    warn!(
+                        url = %retry.url(),
+                        %reason,
+                        ?backoff,
+                        "retrying request {attempt} of {}",
+                        self.retries
+                    );
+
+                    sleep(backoff).await;
+                    request = retry;
+                }
+                _ => return result,
+            }
+        }
+    }
+}
+
+/// Return the time to wait before the given retry, starting at 0 for the first retry
+fn backoff(attempt: u32) -> Duration {
+    INITIAL_BACKOFF
+        .saturating_mul(2u32.saturating_pow(attempt))
+        .min(MAX_BACKOFF)
+}
+
+/// Check whether sending a request more than once has the same effect as sending it once
+fn is_idempotent(method: &Method) -> bool {
+    matches!(
+        *method,
+        Method::GET | Method::HEAD | Method::PUT | Method::DELETE | Method::OPTIONS | Method::TRACE
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use mockito::Server;
+    use pretty_assertions::assert_eq;
+    use tempfile::NamedTempFile;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn execute_retries_server_errors() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("GET", "/app")
+            .with_status(502)
+            .expect(2)
+            .create_async()
+            .await;
+
+        let client = HttpClient::new(None, 1).unwrap();
+        let request = client.client().get(format!("{}/app", server.url()));
+        let response = client.execute(request.build().unwrap()).await.unwrap();
+
+        mock.assert_async().await;
+        assert_eq!(502, response.status().as_u16());
+    }
+
+    #[tokio::test]
+    async fn execute_does_not_retry_server_errors_of_post_requests() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("POST", "/app-manifests/otters/conversions")
+            .with_status(502)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let client = HttpClient::new(None, 3).unwrap();
+        let request = client
+            .client()
+            .post(format!("{}/app-manifests/otters/conversions", server.url()));
+        let response = client.execute(request.build().unwrap()).await.unwrap();
+
+        mock.assert_async().await;
+        assert_eq!(502, response.status().as_u16());
+    }
+
+    #[tokio::test]
+    async fn execute_does_not_retry_client_errors() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("GET", "/app")
+            .with_status(404)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let client = HttpClient::new(None, 3).unwrap();
+        let request = client.client().get(format!("{}/app", server.url()));
+        let response = client.execute(request.build().unwrap()).await.unwrap();
+
+        mock.assert_async().await;
+        assert_eq!(404, response.status().as_u16());
+    }
+
+    #[test]
+    fn backoff_is_capped() {
+        assert_eq!(Duration::from_millis(500), backoff(0));
+        assert_eq!(Duration::from_secs(4), backoff(3));
+        assert_eq!(MAX_BACKOFF, backoff(40));
+    }
+
+    #[test]
+    fn new_fails_for_missing_ca_cert() {
+        let error = HttpClient::new(Some(Path::new("missing.pem")), 0).unwrap_err();
+
+        assert_eq!(
+            "failed to read CA certificate from missing.pem",
+            error.to_string()
+        );
+    }
+
+    #[test]
+    fn new_fails_for_invalid_ca_cert() {
+        let file = NamedTempFile::new().unwrap();
+        std::fs::write(file.path(), "-----BEGIN CERTIFICATE-----\notters\n").unwrap();
+
+        let error = HttpClient::new(Some(file.path()), 0).unwrap_err();
+
+        assert!(error
+            .to_string()
+            .starts_with("failed to parse CA certificate"));
+    }
+
+    #[test]
+    fn trait_send() {
+        fn assert_send<T: Send>() {}
+        assert_send::<HttpClient>();
+    }
+
+    #[test]
+    fn trait_sync() {
+        fn assert_sync<T: Sync>() {}
+        assert_sync::<HttpClient>();
+    }
+
+    #[test]
+    fn trait_unpin() {
+        fn assert_unpin<T: Unpin>() {}
+        assert_unpin::<HttpClient>();
+    }
+}
@@ -0,0 +1,9 @@
+//! Access GitHub's REST API as a GitHub App
+
+pub use self::client::*;
+pub use self::http::*;
+pub use self::token::*;
+
+mod client;
+mod http;
+mod token;
@@ -0,0 +1,166 @@
+//! Tokens to authenticate as a GitHub App or one of its installations
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::{Context, Error};
+use jsonwebtoken::{encode, Algorithm, EncodingKey, Header};
+use reqwest::Method;
+use serde::{Deserialize, Serialize};
+use typed_fields::secret;
+
+use crate::app::App;
+
+use super::GitHubClient;
+
+secret!(Token);
+
+/// Claims of the JSON Web Token that authenticates a GitHub App
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Serialize)]
+struct Claims {
+    /// The time at which the token was issued, backdated to allow for clock drift
+    iat: u64,
+
+    /// The time at which the token expires, at most ten minutes after it was issued
+    exp: u64,
+
+    /// The id of the GitHub App
+    iss: String,
+}
+
+/// Response to the request for an installation access token
+#[derive(Clone, Debug, Deserialize)]
+struct InstallationTokenResponse {
+    /// The installation access token
+    token: Token,
+}
+
+/// Create a JSON Web Token that authenticates as the GitHub App
+///
+/// The token is signed with the app's private key and is valid for nine minutes. It is backdated by
+/// one minute to protect against clock drift between the local machine and GitHub.
+pub fn app_token(app: &App) -> Result<Token, Error> {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .context("system time is before the UNIX epoch")?
+        .as_secs();
+
+    let claims = Claims {
+        iat: now - 60,
+        exp: now + 9 * 60,
+        iss: app.id().to_string(),
+    };
+
+    let key = EncodingKey::from_rsa_pem(app.pem().expose().as_bytes())
+        .context("failed to parse the app's private key")?;
+
+    encode(&Header::new(Algorithm::RS256), &claims, &key)
+        .context("failed to sign JSON Web Token")
+        .map(Into::into)
+}
+
+/// Create an access token for an installation of the GitHub App
+///
+/// The client must be authenticated as the app itself. The returned token can be used to access
+/// the resources of the installation, e.g. its repositories.
+pub async fn installation_token(
+    client: &GitHubClient,
+    installation_id: u64,
+) -> Result<Token, Error> {
+    let request = client.request(
+        Method::POST,
+        &format!("app/installations/{installation_id}/access_tokens"),
+    )?;
+
+    let response: InstallationTokenResponse = client
+        .send(request)
+        .await
+        .with_context(|| format!("failed to create token for installation {installation_id}"))?
+        .json()
+        .await
+        .context("failed to parse installation token")?;
+
+    Ok(response.token)
+}
+
+#[cfg(test)]
+mod tests {
+    use jsonwebtoken::{decode, DecodingKey, Validation};
+    use mockito::{Matcher, Server};
+    use pretty_assertions::assert_eq;
+    use url::Url;
+
+    use crate::github::HttpClient;
+
+    use super::*;
+
+    const PRIVATE_KEY: &str = include_str!("../../tests/fixtures/private-key.pem");
+
+    const PUBLIC_KEY: &str = include_str!("../../tests/fixtures/public-key.pem");
+
+    fn app() -> App {
+        App::builder()
+            .id(42)
+            .name("app")
+            .client_id("client_id")
+            .client_secret("client_secret")
+            .pem(PRIVATE_KEY)
+            .build()
+    }
+
+    #[derive(Deserialize)]
+    struct DecodedClaims {
+        iss: String,
+    }
+
+    #[test]
+    fn app_token_is_signed_with_private_key() {
+        let token = app_token(&app()).unwrap();
+
+        let claims = decode::<DecodedClaims>(
+            token.expose(),
+            &DecodingKey::from_rsa_pem(PUBLIC_KEY.as_bytes()).unwrap(),
+            &Validation::new(Algorithm::RS256),
+        )
+        .unwrap()
+        .claims;
+
+        assert_eq!("42", claims.iss);
+    }
+
+    #[test]
+    fn app_token_errors_on_invalid_private_key() {
+        let app = App::builder()
+            .id(42)
+            .name("app")
+            .client_id("client_id")
+            .client_secret("client_secret")
+            .pem("not a private key")
+            .build();
+
+        let error = app_token(&app).unwrap_err();
+
+        assert_eq!("failed to parse the app's private key", error.to_string());
+    }
+
+    #[tokio::test]
+    async fn installation_token_requests_token() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("POST", "/app/installations/7/access_tokens")
+            .match_header("authorization", Matcher::Regex("^Bearer ey".into()))
+            .with_status(201)
+            .with_body(r#"{"token":"ghs_otters"}"#)
+            .create_async()
+            .await;
+
+        let client = GitHubClient::new(
+            &Url::parse(&server.url()).unwrap(),
+            &HttpClient::new(None, 0).unwrap(),
+        )
+        .with_token(app_token(&app()).unwrap());
+        let token = installation_token(&client, 7).await.unwrap();
+
+        mock.assert_async().await;
+        assert_eq!("ghs_otters", token.expose());
+    }
+}
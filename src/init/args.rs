@@ -0,0 +1,28 @@
+//! Command-line arguments for the `init` subcommand
+
+use std::path::PathBuf;
+
+use clap::Parser;
+use getset::{CopyGetters, Getters};
+use typed_builder::TypedBuilder;
+
+/// Command-line arguments for the `init` subcommand
+///
+/// The `init` subcommand asks a few questions about the GitHub App and writes a manifest for it.
+/// The format of the manifest is detected from the extension of the output file.
+#[derive(
+    Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Parser, CopyGetters, Getters, TypedBuilder,
+)]
+pub struct InitArgs {
+    /// The path of the manifest file to write
+    #[arg(short, long, default_value = "manifest.json")]
+    #[builder(setter(into))]
+    #[getset(get = "pub")]
+    output: PathBuf,
+
+    /// Overwrite the manifest file if it already exists
+    #[arg(long)]
+    #[builder(default)]
+    #[getset(get_copy = "pub")]
+    force: bool,
+}
@@ -0,0 +1,468 @@
+//! Command to scaffold a manifest for a new GitHub App
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use anyhow::{anyhow, Context, Error};
+use async_trait::async_trait;
+use dialoguer::theme::ColorfulTheme;
+use dialoguer::{Confirm, Input, MultiSelect, Select};
+use serde_json::json;
+use typed_builder::TypedBuilder;
+use url::Url;
+
+use crate::cli::Args;
+use crate::manifest::{AccessLevel, Event, Format, HookAttributes, Manifest, Permission};
+use crate::Execute;
+
+use super::InitArgs;
+
+/// The file in which the app's secrets are saved
+const ENV_FILE: &str = ".env";
+
+/// Answers to the questions of the wizard
+#[derive(Clone, Eq, PartialEq, Debug, TypedBuilder)]
+struct Answers {
+    /// The name of the app, which may contain placeholders
+    #[builder(setter(into))]
+    name: String,
+
+    /// The homepage of the app
+    #[builder(setter(into))]
+    url: String,
+
+    /// The URL to which the app's webhooks are delivered
+    #[builder(default, setter(into, strip_option))]
+    webhook_url: Option<String>,
+
+    /// Whether the app is available to the public
+    #[builder(default)]
+    public: bool,
+
+    /// The permissions of the app
+    #[builder(default)]
+    permissions: BTreeMap<Permission, AccessLevel>,
+
+    /// The events to which the app subscribes
+    #[builder(default)]
+    events: Vec<Event>,
+}
+
+/// Scaffold a manifest for a new GitHub App
+///
+/// This command asks for the name, homepage, webhook, visibility, permissions, and events of the
+/// app, and writes them as a manifest. Events are offered only if the app has a permission that
+/// allows it to receive them, so that the manifest is always valid. The .env file to which the
+/// app's secrets will be saved is added to the .gitignore file.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct InitCommand<'a> {
+    /// The arguments for the command
+    args: &'a InitArgs,
+}
+
+impl<'a> InitCommand<'a> {
+    /// Create a new instance of the command
+    pub fn new(args: &'a InitArgs) -> Self {
+        Self { args }
+    }
+}
+
+#[async_trait]
+impl<'a> Execute for InitCommand<'a> {
+    async fn execute(&self, global_args: &Args) -> Result<(), Error> {
+        let console = global_args.output();
+        let output = self.args.output();
+        if output.exists() && !self.args.force() {
+            return Err(anyhow!(
+                "{} already exists, pass --force to overwrite it",
+                output.display()
+            ));
+        }
+
+        let directory = std::env::current_dir().context("failed to read current directory")?;
+        let project = directory
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_else(|| "app".into());
+
+        let answers = ask(&project).context("failed to read answers")?;
+        let manifest = Format::from_path(output).serialize(&build_manifest(answers))?;
+
+        std::fs::write(output, manifest)
+            .with_context(|| format!("failed to write manifest to {}", output.display()))?;
+        console.message(format!("Wrote manifest to {}", output.display()));
+
+        let updated_gitignore = ignore_env_file(&directory)?;
+        if let Some(gitignore) = &updated_gitignore {
+            console.message(format!("Added {ENV_FILE} to {}", gitignore.display()));
+        }
+        let gitignore_updated = updated_gitignore.is_some();
+
+        console.message(format!(
+            "Register the app with: github-dev-app register {}",
+            output.display()
+        ));
+        console.result(&json!({ "manifest": output, "gitignore_updated": gitignore_updated }))?;
+
+        Ok(())
+    }
+}
+
+/// Ask the questions of the wizard
+fn ask(project: &str) -> Result<Answers, Error> {
+    let theme = ColorfulTheme::default();
+
+    let name: String = Input::with_theme(&theme)
+        .with_prompt("Name of the app ({{user}} is replaced with your username)")
+        .default(format!("{project}-dev-{{{{user}}}}"))
+        .interact_text()?;
+
+    let url: String = Input::with_theme(&theme)
+        .with_prompt("Homepage of the app")
+        .default("https://github.com".into())
+        .validate_with(|input: &String| validate_url(input))
+        .interact_text()?;
+
+    let webhook = Select::with_theme(&theme)
+        .with_prompt("How should the app receive webhooks?")
+        .items(&[
+            "Forward them to this machine through a smee.io channel",
+            "Send them to a public URL",
+            "The app does not receive webhooks",
+        ])
+        .default(0)
+        .interact()?;
+    let webhook_url = match webhook {
+        0 => Some(
+            Input::<String>::with_theme(&theme)
+                .with_prompt("URL of the smee.io channel (create one at https://smee.io/new)")
+                .validate_with(|input: &String| validate_url(input))
+                .interact_text()?,
+        ),
+        1 => Some(
+            Input::<String>::with_theme(&theme)
+                .with_prompt("URL of the webhook")
+                .validate_with(|input: &String| validate_url(input))
+                .interact_text()?,
+        ),
+        _ => None,
+    };
+
+    let public = Confirm::with_theme(&theme)
+        .with_prompt("Can anyone install the app?")
+        .default(false)
+        .interact()?;
+
+    let names: Vec<&str> = Permission::ALL.iter().map(Permission::as_str).collect();
+    let selected = MultiSelect::with_theme(&theme)
+        .with_prompt("Permissions of the app (space to select, enter to confirm)")
+        .items(&names)
+        .interact()?;
+
+    let mut permissions = BTreeMap::new();
+    for permission in selected.into_iter().map(|index| Permission::ALL[index]) {
+        let levels = permission.access_levels();
+        let level = Select::with_theme(&theme)
+            .with_prompt(format!("Access level of {permission}"))
+            .items(levels)
+            .default(0)
+            .interact()?;
+
+        permissions.insert(permission, levels[level]);
+    }
+
+    let mut events = Vec::new();
+    if webhook_url.is_some() {
+        let available = available_events(&permissions);
+        let names: Vec<&str> = available.iter().map(Event::as_str).collect();
+        let selected = MultiSelect::with_theme(&theme)
+            .with_prompt("Webhook events of the app (only events allowed by the permissions)")
+            .items(&names)
+            .interact()?;
+
+        events = selected
+            .into_iter()
+            .map(|index| available[index].clone())
+            .collect();
+    }
+
+    Ok(Answers {
+        name,
+        url,
+        webhook_url,
+        public,
+        permissions,
+        events,
+    })
+}
+
+/// Validate that an answer is a URL
+fn validate_url(input: &str) -> Result<(), String> {
+    Url::parse(input)
+        .map(|_| ())
+        .map_err(|error| error.to_string())
+}
+
+/// Return the events that an app with the given permissions can subscribe to
+fn available_events(permissions: &BTreeMap<Permission, AccessLevel>) -> Vec<Event> {
+    Event::ALL
+        .iter()
+        .filter(|event| {
+            event.required_permissions().is_some_and(|required| {
+                required.is_empty()
+                    || required
+                        .iter()
+                        .any(|permission| permissions.contains_key(permission))
+            })
+        })
+        .cloned()
+        .collect()
+}
+
+/// Build the manifest from the answers of the wizard
+fn build_manifest(answers: Answers) -> Manifest {
+    let mut manifest = Manifest::builder().url(answers.url.into()).build();
+
+    manifest.set_name(Some(answers.name.into()));
+    manifest.set_public(Some(answers.public));
+
+    if let Some(webhook_url) = answers.webhook_url {
+        manifest.set_hook_attributes(Some(HookAttributes::builder().url(webhook_url).build()));
+    }
+
+    if !answers.permissions.is_empty() {
+        manifest.set_default_permissions(Some(answers.permissions));
+    }
+
+    if !answers.events.is_empty() {
+        manifest.set_default_events(Some(answers.events));
+    }
+
+    manifest
+}
+
+/// Add the .env file in a directory to the .gitignore file of its repository
+///
+/// The .gitignore file at the root of the git repository is used, or the one in the directory if
+/// it is not in a repository. The file is created if it does not exist. Git decides whether the
+/// .env file is already ignored, so that patterns like `*.env` and nested .gitignore files are
+/// respected. Returns the .gitignore file if it was changed.
+fn ignore_env_file(directory: &Path) -> Result<Option<PathBuf>, Error> {
+    let gitignore = gitignore_path(directory);
+
+    let mut content = match std::fs::read_to_string(&gitignore) {
+        Ok(content) => content,
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(error) => {
+            return Err(error).with_context(|| format!("failed to read {}", gitignore.display()))
+        }
+    };
+
+    let is_ignored = match is_ignored_by_git(directory, ENV_FILE) {
+        Some(is_ignored) => is_ignored,
+        None => content
+            .lines()
+            .map(str::trim)
+            .any(|line| line == ENV_FILE || line == format!("/{ENV_FILE}")),
+    };
+    if is_ignored {
+        return Ok(None);
+    }
+
+    if !content.is_empty() && !content.ends_with('\n') {
+        content.push('\n');
+    }
+    content.push_str(ENV_FILE);
+    content.push('\n');
+
+    std::fs::write(&gitignore, content)
+        .with_context(|| format!("failed to write {}", gitignore.display()))?;
+
+    Ok(Some(gitignore))
+}
+
+/// Return the .gitignore file at the root of the git repository that contains a directory
+///
+/// Falls back to the .gitignore file in the directory if it is not in a git repository, or if git
+/// is not installed.
+fn gitignore_path(directory: &Path) -> PathBuf {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(directory)
+        .args(["rev-parse", "--show-toplevel"])
+        .output();
+
+    match output {
+        Ok(output) if output.status.success() => {
+            PathBuf::from(String::from_utf8_lossy(&output.stdout).trim()).join(".gitignore")
+        }
+        _ => directory.join(".gitignore"),
+    }
+}
+
+/// Ask git whether a file in a directory is ignored, or return `None` if git cannot tell
+fn is_ignored_by_git(directory: &Path, file: &str) -> Option<bool> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(directory)
+        .args(["check-ignore", "--quiet", "--", file])
+        .output()
+        .ok()?;
+
+    match output.status.code() {
+        Some(0) => Some(true),
+        Some(1) => Some(false),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use tempfile::TempDir;
+
+    use crate::manifest::Variables;
+
+    use super::*;
+
+    #[test]
+    fn available_events_requires_permissions() {
+        let permissions = BTreeMap::from([(Permission::Checks, AccessLevel::Write)]);
+
+        let events = available_events(&permissions);
+
+        assert!(events.contains(&Event::CheckRun));
+        assert!(events.contains(&Event::Meta));
+        assert!(!events.contains(&Event::Issues));
+    }
+
+    #[test]
+    fn build_manifest_writes_answers() {
+        let answers = Answers::builder()
+            .name("octoapp-dev-{{user}}")
+            .url("https://example.com")
+            .webhook_url("https://smee.io/otters")
+            .permissions(BTreeMap::from([(Permission::Issues, AccessLevel::Write)]))
+            .events(vec![Event::Issues])
+            .build();
+
+        let json = serde_json::to_value(build_manifest(answers)).unwrap();
+
+        assert_eq!(
+            serde_json::json!({
+                "name": "octoapp-dev-{{user}}",
+                "url": "https://example.com",
+                "hook_attributes": { "url": "https://smee.io/otters" },
+                "public": false,
+                "default_events": ["issues"],
+                "default_permissions": { "issues": "write" }
+            }),
+            json
+        );
+    }
+
+    #[test]
+    fn build_manifest_creates_valid_manifest() {
+        let answers = Answers::builder()
+            .name("octoapp")
+            .url("https://example.com")
+            .build();
+
+        let json = serde_json::to_value(build_manifest(answers)).unwrap();
+
+        assert!(Manifest::from_value(json, &Variables::default()).is_ok());
+    }
+
+    fn git(directory: &Path, args: &[&str]) {
+        let status = Command::new("git")
+            .arg("-C")
+            .arg(directory)
+            .args(args)
+            .output()
+            .unwrap()
+            .status;
+        assert!(status.success());
+    }
+
+    #[test]
+    fn ignore_env_file_creates_gitignore() {
+        let directory = TempDir::new().unwrap();
+        let gitignore = directory.path().join(".gitignore");
+
+        assert_eq!(
+            Some(gitignore.clone()),
+            ignore_env_file(directory.path()).unwrap()
+        );
+        assert_eq!(".env\n", std::fs::read_to_string(&gitignore).unwrap());
+    }
+
+    #[test]
+    fn ignore_env_file_appends_to_gitignore() {
+        let directory = TempDir::new().unwrap();
+        let gitignore = directory.path().join(".gitignore");
+        std::fs::write(&gitignore, "/target").unwrap();
+
+        assert!(ignore_env_file(directory.path()).unwrap().is_some());
+        assert_eq!(
+            "/target\n.env\n",
+            std::fs::read_to_string(&gitignore).unwrap()
+        );
+    }
+
+    #[test]
+    fn ignore_env_file_skips_ignored_env_file() {
+        let directory = TempDir::new().unwrap();
+        let gitignore = directory.path().join(".gitignore");
+        std::fs::write(&gitignore, "/target\n/.env\n").unwrap();
+
+        assert_eq!(None, ignore_env_file(directory.path()).unwrap());
+        assert_eq!(
+            "/target\n/.env\n",
+            std::fs::read_to_string(&gitignore).unwrap()
+        );
+    }
+
+    #[test]
+    fn ignore_env_file_updates_gitignore_of_repository() {
+        let directory = TempDir::new().unwrap();
+        git(directory.path(), &["init", "--quiet"]);
+        let subdirectory = directory.path().join("bot");
+        std::fs::create_dir(&subdirectory).unwrap();
+
+        let gitignore = ignore_env_file(&subdirectory).unwrap().unwrap();
+
+        assert_eq!(
+            directory.path().canonicalize().unwrap(),
+            gitignore.parent().unwrap().canonicalize().unwrap()
+        );
+        assert!(!subdirectory.join(".gitignore").exists());
+    }
+
+    #[test]
+    fn ignore_env_file_respects_patterns_of_repository() {
+        let directory = TempDir::new().unwrap();
+        git(directory.path(), &["init", "--quiet"]);
+        std::fs::write(directory.path().join(".gitignore"), "*.env*\n").unwrap();
+
+        assert_eq!(None, ignore_env_file(directory.path()).unwrap());
+    }
+
+    #[test]
+    fn trait_send() {
+        fn assert_send<T: Send>() {}
+        assert_send::<InitCommand>();
+    }
+
+    #[test]
+    fn trait_sync() {
+        fn assert_sync<T: Sync>() {}
+        assert_sync::<InitCommand>();
+    }
+
+    #[test]
+    fn trait_unpin() {
+        fn assert_unpin<T: Unpin>() {}
+        assert_unpin::<InitCommand>();
+    }
+}
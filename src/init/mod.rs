@@ -0,0 +1,7 @@
+//! Scaffold a manifest for a new GitHub App
+
+pub use self::args::*;
+pub use self::command::*;
+
+mod args;
+mod command;
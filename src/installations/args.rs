@@ -0,0 +1,69 @@
+//! Command-line arguments for the `installations` subcommand
+
+use std::path::PathBuf;
+
+use clap::{Parser, Subcommand};
+use getset::Getters;
+use typed_builder::TypedBuilder;
+use url::Url;
+
+/// Command-line arguments for the `installations` subcommand
+///
+/// The `installations` subcommand groups the commands that manage the installations of the GitHub
+/// App. All commands authenticate as the app using the private key in the .env file.
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Parser, Getters, TypedBuilder)]
+pub struct InstallationsArgs {
+    /// The command to execute
+    #[command(subcommand)]
+    #[getset(get = "pub")]
+    command: InstallationsSubcommand,
+
+    /// The endpoint of the GitHub API
+    #[arg(long, global = true, default_value_t = Url::parse("https://api.github.com").unwrap())]
+    #[builder(setter(into))]
+    #[getset(get = "pub")]
+    github: Url,
+
+    /// The .env file with the app's id and private key
+    #[arg(long, global = true, default_value = ".env")]
+    #[builder(setter(into))]
+    #[getset(get = "pub")]
+    env_file: PathBuf,
+
+    /// The prefix of the variables in the .env file, e.g. `STAGING_` for `STAGING_APP_ID`
+    #[arg(long, global = true, default_value = "GITHUB_")]
+    #[builder(default = String::from("GITHUB_"), setter(into))]
+    #[getset(get = "pub")]
+    prefix: String,
+}
+
+/// Commands to manage the installations of a GitHub App
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Subcommand)]
+pub enum InstallationsSubcommand {
+    /// List the installations of the GitHub App
+    List,
+
+    /// List the repositories that an installation can access
+    Repos {
+        /// The id of the installation
+        id: u64,
+    },
+
+    /// Suspend an installation, which blocks the app from accessing its resources
+    Suspend {
+        /// The id of the installation
+        id: u64,
+    },
+
+    /// Unsuspend a suspended installation
+    Unsuspend {
+        /// The id of the installation
+        id: u64,
+    },
+
+    /// Uninstall the GitHub App from an account
+    Delete {
+        /// The id of the installation
+        id: u64,
+    },
+}
@@ -0,0 +1,355 @@
+//! Command to manage the installations of a GitHub App
+
+use anyhow::{Context, Error};
+use async_trait::async_trait;
+use reqwest::Method;
+use serde_json::json;
+
+use crate::cli::Args;
+use crate::env::load_from_env;
+use crate::github::{app_token, installation_token, GitHubClient};
+use crate::Execute;
+
+use super::installation::{Installation, Repository, RepositoryPage};
+use super::{InstallationsArgs, InstallationsSubcommand};
+
+/// Manage the installations of a GitHub App
+///
+/// This command lists, suspends, unsuspends, and deletes the installations of the GitHub App. It
+/// authenticates as the app with a JSON Web Token that is signed with the private key from the .env
+/// file.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct InstallationsCommand<'a> {
+    /// The arguments for the command
+    args: &'a InstallationsArgs,
+}
+
+impl<'a> InstallationsCommand<'a> {
+    /// Create a new instance of the command
+    pub fn new(args: &'a InstallationsArgs) -> Self {
+        Self { args }
+    }
+}
+
+#[async_trait]
+impl<'a> Execute for InstallationsCommand<'a> {
+    async fn execute(&self, global_args: &Args) -> Result<(), Error> {
+        let output = global_args.output();
+        let app = load_from_env(self.args.env_file(), self.args.prefix())?;
+        let client = GitHubClient::new(self.args.github(), &global_args.http_client()?)
+            .with_token(app_token(&app)?);
+
+        match *self.args.command() {
+            InstallationsSubcommand::List => {
+                let installations = list_installations(&client).await?;
+
+                if output.is_json() {
+                    output.result(&json!({ "installations": installations }))?;
+                } else {
+                    print!("{}", format_installations(&installations));
+                }
+            }
+            InstallationsSubcommand::Repos { id } => {
+                let repositories = list_repositories(&client, id).await?;
+
+                if output.is_json() {
+                    output.result(&json!({ "id": id, "repositories": repositories }))?;
+                } else {
+                    for repository in &repositories {
+                        println!("{}", format_repository(repository));
+                    }
+                }
+            }
+            InstallationsSubcommand::Suspend { id } => {
+                change_installation(&client, Method::PUT, &format!("{id}/suspended")).await?;
+                output.message(format!("Suspended installation {id}"));
+                output.result(&json!({ "id": id, "suspended": true }))?;
+            }
+            InstallationsSubcommand::Unsuspend { id } => {
+                change_installation(&client, Method::DELETE, &format!("{id}/suspended")).await?;
+                output.message(format!("Unsuspended installation {id}"));
+                output.result(&json!({ "id": id, "suspended": false }))?;
+            }
+            InstallationsSubcommand::Delete { id } => {
+                change_installation(&client, Method::DELETE, &id.to_string()).await?;
+                output.message(format!("Deleted installation {id}"));
+                output.result(&json!({ "id": id, "deleted": true }))?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// List all installations of the GitHub App
+async fn list_installations(client: &GitHubClient) -> Result<Vec<Installation>, Error> {
+    let pages = client
+        .get_all_pages("app/installations?per_page=100")
+        .await
+        .context("failed to list installations")?;
+
+    let mut installations = Vec::new();
+    for page in pages {
+        installations.extend(
+            serde_json::from_value::<Vec<Installation>>(page)
+                .context("failed to parse installations")?,
+        );
+    }
+
+    Ok(installations)
+}
+
+/// List the repositories that an installation can access
+///
+/// The repositories of an installation can only be listed with an installation access token. The
+/// token is created on the fly using the app's credentials.
+async fn list_repositories(client: &GitHubClient, id: u64) -> Result<Vec<Repository>, Error> {
+    let token = installation_token(client, id).await?;
+    let installation_client = GitHubClient::new(client.endpoint(), client.http()).with_token(token);
+
+    let pages = installation_client
+        .get_all_pages("installation/repositories?per_page=100")
+        .await
+        .with_context(|| format!("failed to list repositories of installation {id}"))?;
+
+    let mut repositories = Vec::new();
+    for page in pages {
+        let page: RepositoryPage =
+            serde_json::from_value(page).context("failed to parse repositories")?;
+
+        repositories.extend(page.repositories().iter().cloned());
+    }
+
+    Ok(repositories)
+}
+
+/// Change the state of an installation
+///
+/// Suspending, unsuspending, and deleting an installation are requests without a body to a path
+/// below `app/installations`.
+async fn change_installation(
+    client: &GitHubClient,
+    method: Method,
+    path: &str,
+) -> Result<(), Error> {
+    let request = client.request(method.clone(), &format!("app/installations/{path}"))?;
+
+    client
+        .send(request)
+        .await
+        .with_context(|| format!("failed to {method} app/installations/{path}"))?;
+
+    Ok(())
+}
+
+/// Format a repository with its visibility
+fn format_repository(repository: &Repository) -> String {
+    if repository.private() {
+        format!("{} (private)", repository.full_name())
+    } else {
+        repository.full_name().clone()
+    }
+}
+
+/// Format the installations as a table
+fn format_installations(installations: &[Installation]) -> String {
+    let mut rows = vec![[
+        "ID".to_string(),
+        "ACCOUNT".to_string(),
+        "TYPE".to_string(),
+        "REPOSITORIES".to_string(),
+        "SUSPENDED".to_string(),
+    ]];
+
+    for installation in installations {
+        rows.push([
+            installation.id().to_string(),
+            installation
+                .account()
+                .as_ref()
+                .map(|account| account.login().clone())
+                .unwrap_or_default(),
+            installation.target_type().clone(),
+            installation.repository_selection().clone(),
+            if installation.suspended_at().is_some() {
+                "yes".to_string()
+            } else {
+                "no".to_string()
+            },
+        ]);
+    }
+
+    let widths: Vec<usize> = (0..5)
+        .map(|column| rows.iter().map(|row| row[column].len()).max().unwrap_or(0))
+        .collect();
+
+    rows.iter()
+        .map(|row| {
+            let cells: Vec<String> = row
+                .iter()
+                .zip(&widths)
+                .map(|(cell, width)| format!("{cell:width$}"))
+                .collect();
+
+            format!("{}\n", cells.join("  ").trim_end())
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+    use mockito::{Matcher, Server};
+    use pretty_assertions::assert_eq;
+    use url::Url;
+
+    use crate::app::App;
+    use crate::github::HttpClient;
+
+    use super::*;
+
+    const PRIVATE_KEY: &str = include_str!("../../tests/fixtures/private-key.pem");
+
+    fn client(server: &Server) -> GitHubClient {
+        let app = App::builder()
+            .id(1)
+            .name("app")
+            .client_id("client_id")
+            .client_secret("client_secret")
+            .pem(PRIVATE_KEY)
+            .build();
+
+        GitHubClient::new(
+            &Url::parse(&server.url()).unwrap(),
+            &HttpClient::new(None, 0).unwrap(),
+        )
+        .with_token(app_token(&app).unwrap())
+    }
+
+    #[tokio::test]
+    async fn list_installations_returns_installations() {
+        let mut server = Server::new_async().await;
+        let _mock = server
+            .mock("GET", "/app/installations?per_page=100")
+            .with_body(indoc! {r#"
+                [
+                    {
+                        "id": 1,
+                        "account": { "login": "octocat" },
+                        "target_type": "User",
+                        "repository_selection": "selected",
+                        "suspended_at": "2024-06-01T12:00:00Z"
+                    },
+                    {
+                        "id": 22,
+                        "account": { "login": "otterbuild" },
+                        "target_type": "Organization",
+                        "repository_selection": "all",
+                        "suspended_at": null
+                    }
+                ]
+            "#})
+            .create_async()
+            .await;
+
+        let installations = list_installations(&client(&server)).await.unwrap();
+
+        assert_eq!(
+            indoc! {"
+                ID  ACCOUNT     TYPE          REPOSITORIES  SUSPENDED
+                1   octocat     User          selected      yes
+                22  otterbuild  Organization  all           no
+            "},
+            format_installations(&installations)
+        );
+    }
+
+    #[tokio::test]
+    async fn list_repositories_uses_installation_token() {
+        let mut server = Server::new_async().await;
+        let _token = server
+            .mock("POST", "/app/installations/22/access_tokens")
+            .with_status(201)
+            .with_body(r#"{"token":"ghs_otters"}"#)
+            .create_async()
+            .await;
+        let _repositories = server
+            .mock("GET", "/installation/repositories?per_page=100")
+            .match_header("authorization", "Bearer ghs_otters")
+            .with_body(indoc! {r#"
+                {
+                    "total_count": 2,
+                    "repositories": [
+                        { "full_name": "otterbuild/github-dev-app", "private": false },
+                        { "full_name": "otterbuild/secret", "private": true }
+                    ]
+                }
+            "#})
+            .create_async()
+            .await;
+
+        let repositories: Vec<String> = list_repositories(&client(&server), 22)
+            .await
+            .unwrap()
+            .iter()
+            .map(format_repository)
+            .collect();
+
+        assert_eq!(
+            vec!["otterbuild/github-dev-app", "otterbuild/secret (private)"],
+            repositories
+        );
+    }
+
+    #[tokio::test]
+    async fn change_installation_suspends_installation() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("PUT", "/app/installations/22/suspended")
+            .match_header("authorization", Matcher::Regex("^Bearer ey".into()))
+            .with_status(204)
+            .create_async()
+            .await;
+
+        change_installation(&client(&server), Method::PUT, "22/suspended")
+            .await
+            .unwrap();
+
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn change_installation_errors_on_missing_installation() {
+        let mut server = Server::new_async().await;
+        let _mock = server
+            .mock("DELETE", "/app/installations/404")
+            .with_status(404)
+            .with_body(r#"{"message":"Not Found"}"#)
+            .create_async()
+            .await;
+
+        let error = change_installation(&client(&server), Method::DELETE, "404")
+            .await
+            .unwrap_err();
+
+        assert_eq!("failed to DELETE app/installations/404", error.to_string());
+    }
+
+    #[test]
+    fn trait_send() {
+        fn assert_send<T: Send>() {}
+        assert_send::<InstallationsCommand>();
+    }
+
+    #[test]
+    fn trait_sync() {
+        fn assert_sync<T: Sync>() {}
+        assert_sync::<InstallationsCommand>();
+    }
+
+    #[test]
+    fn trait_unpin() {
+        fn assert_unpin<T: Unpin>() {}
+        assert_unpin::<InstallationsCommand>();
+    }
+}
@@ -0,0 +1,110 @@
+//! Installations of a GitHub App and the repositories they can access
+
+use getset::{CopyGetters, Getters};
+use serde::{Deserialize, Serialize};
+
+/// An installation of a GitHub App
+///
+/// A GitHub App is installed on a user or organization account, and can access either all or a
+/// selected set of the account's repositories. Installations can be suspended, in which case the
+/// app can no longer access the account's resources.
+#[derive(
+    Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, CopyGetters, Getters, Deserialize, Serialize,
+)]
+pub struct Installation {
+    /// The unique identifier of the installation
+    #[getset(get_copy = "pub")]
+    id: u64,
+
+    /// The account on which the app is installed
+    #[getset(get = "pub")]
+    account: Option<Account>,
+
+    /// The type of the account, e.g. `User` or `Organization`
+    #[getset(get = "pub")]
+    target_type: String,
+
+    /// Whether the installation can access `all` or only `selected` repositories
+    #[getset(get = "pub")]
+    repository_selection: String,
+
+    /// The time at which the installation was suspended
+    #[getset(get = "pub")]
+    suspended_at: Option<String>,
+}
+
+/// The account on which a GitHub App is installed
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Getters, Deserialize, Serialize)]
+pub struct Account {
+    /// The login of the user or organization, or the slug of the enterprise
+    #[getset(get = "pub")]
+    #[serde(alias = "slug")]
+    login: String,
+}
+
+/// A repository that an installation can access
+#[derive(
+    Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, CopyGetters, Getters, Deserialize, Serialize,
+)]
+pub struct Repository {
+    /// The full name of the repository, e.g. `otterbuild/github-dev-app`
+    #[getset(get = "pub")]
+    full_name: String,
+
+    /// Whether the repository is private
+    #[getset(get_copy = "pub")]
+    private: bool,
+}
+
+/// A page of repositories that an installation can access
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Getters, Deserialize)]
+pub struct RepositoryPage {
+    /// The repositories on the page
+    #[getset(get = "pub")]
+    repositories: Vec<Repository>,
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn trait_deserialize() {
+        let installation: Installation = serde_json::from_str(indoc! {r#"
+            {
+                "id": 1,
+                "account": { "slug": "otterbuild-enterprise" },
+                "target_type": "Enterprise",
+                "repository_selection": "all",
+                "suspended_at": null
+            }
+        "#})
+        .unwrap();
+
+        assert_eq!(
+            "otterbuild-enterprise",
+            installation.account().as_ref().unwrap().login()
+        );
+    }
+
+    #[test]
+    fn trait_send() {
+        fn assert_send<T: Send>() {}
+        assert_send::<Installation>();
+    }
+
+    #[test]
+    fn trait_sync() {
+        fn assert_sync<T: Sync>() {}
+        assert_sync::<Installation>();
+    }
+
+    #[test]
+    fn trait_unpin() {
+        fn assert_unpin<T: Unpin>() {}
+        assert_unpin::<Installation>();
+    }
+}
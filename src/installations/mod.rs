@@ -0,0 +1,8 @@
+//! Manage the installations of a GitHub App
+
+pub use self::args::*;
+pub use self::command::*;
+
+mod args;
+mod command;
+mod installation;
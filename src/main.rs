@@ -9,16 +9,38 @@
 #![warn(clippy::missing_docs_in_private_items)]
 #![warn(missing_docs)]
 
+use std::process::ExitCode;
+
 use anyhow::Error;
 use async_trait::async_trait;
-use clap::Parser;
 
+use crate::api::ApiCommand;
 use crate::cli::{Args, Command};
+use crate::config::Config;
+use crate::doctor::DoctorCommand;
+use crate::export_manifest::ExportManifestCommand;
+use crate::init::InitCommand;
+use crate::installations::InstallationsCommand;
+use crate::manifest::ManifestCommand;
 use crate::register::RegisterCommand;
+use crate::tunnel::TunnelCommand;
 
+mod api;
+mod app;
 mod cli;
+mod config;
+mod doctor;
+mod env;
+mod error;
+mod export_manifest;
+mod github;
+mod init;
+mod installations;
+mod logging;
 mod manifest;
+mod output;
 mod register;
+mod tunnel;
 
 /// Execute a command
 ///
@@ -30,12 +52,27 @@ trait Execute {
 }
 
 #[tokio::main]
-async fn main() -> Result<(), Error> {
-    let global_args = Args::parse();
+async fn main() -> ExitCode {
+    // Errors in the configuration are reported before the `--json` flag has been parsed
+    let global_args = match Args::parse_with_config(Config::load()) {
+        Ok(global_args) => global_args,
+        Err(error) => return error::report(&error, false),
+    };
+    logging::init(global_args.verbose());
 
     let command: Box<dyn Execute> = match global_args.command() {
+        Command::Api(args) => Box::new(ApiCommand::new(args)),
+        Command::Doctor(args) => Box::new(DoctorCommand::new(args)),
+        Command::ExportManifest(args) => Box::new(ExportManifestCommand::new(args)),
+        Command::Init(args) => Box::new(InitCommand::new(args)),
+        Command::Installations(args) => Box::new(InstallationsCommand::new(args)),
+        Command::Manifest(args) => Box::new(ManifestCommand::new(args)),
         Command::Register(args) => Box::new(RegisterCommand::new(args)),
+        Command::Tunnel(args) => Box::new(TunnelCommand::new(args)),
     };
 
-    command.execute(&global_args).await
+    match command.execute(&global_args).await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => error::report(&error, global_args.json()),
+    }
 }
@@ -0,0 +1,39 @@
+//! Command-line arguments for the `manifest` subcommand
+
+use std::path::PathBuf;
+
+use clap::{Parser, Subcommand};
+use getset::Getters;
+use typed_builder::TypedBuilder;
+
+/// Command-line arguments for the `manifest` subcommand
+///
+/// The `manifest` subcommand groups the commands that work with manifest files without registering
+/// an app.
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Parser, Getters, TypedBuilder)]
+pub struct ManifestArgs {
+    /// The command to execute
+    #[command(subcommand)]
+    #[getset(get = "pub")]
+    command: ManifestSubcommand,
+}
+
+/// Commands to work with manifest files
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Subcommand)]
+pub enum ManifestSubcommand {
+    /// Check a manifest for invalid URLs, unknown permissions and events, and missing permissions
+    Check {
+        /// The path to the manifest file
+        ///
+        /// Defaults to the `[package.metadata.github-app]` or `[workspace.metadata.github-app]`
+        /// table in the `Cargo.toml` file of the current package.
+        path: Option<PathBuf>,
+
+        /// The profile whose overlay is merged into the manifest before it is checked
+        #[arg(long, requires = "path")]
+        profile: Option<String>,
+    },
+
+    /// Print the JSON Schema of the manifest format, e.g. for editors or pre-commit hooks
+    Schema,
+}
@@ -0,0 +1,179 @@
+//! Manifests that are embedded in the metadata of a Cargo package or workspace
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Context, Error};
+use getset::Getters;
+use serde_json::Value;
+
+/// The key of the manifest in the metadata tables of Cargo.toml
+const METADATA_KEY: &str = "github-app";
+
+/// A manifest that was found in a Cargo.toml file
+#[derive(Clone, Eq, PartialEq, Debug, Getters)]
+pub struct CargoManifest {
+    /// The path to the Cargo.toml file
+    #[getset(get = "pub")]
+    path: PathBuf,
+
+    /// The table that contains the manifest, e.g. `package.metadata.github-app`
+    #[getset(get = "pub")]
+    table: String,
+
+    /// The manifest
+    #[getset(get = "pub")]
+    manifest: Value,
+}
+
+/// Find the manifest in the metadata of the Cargo package or workspace in the current directory
+///
+/// Commands fall back to this manifest when no manifest file is given, and fail if there is none.
+pub fn find_in_current_directory() -> Result<CargoManifest, Error> {
+    let directory = std::env::current_dir().context("failed to read current directory")?;
+
+    find_in_cargo_metadata(&directory)?.ok_or_else(|| {
+        anyhow!("no manifest given, and no [package.metadata.github-app] table found in Cargo.toml")
+    })
+}
+
+/// Find a manifest in the metadata of the Cargo package or workspace that contains a directory
+///
+/// The manifest is read from the `[package.metadata.github-app]` table of the nearest Cargo.toml.
+/// If the package does not define a manifest, the `[workspace.metadata.github-app]` table of the
+/// workspace is used instead.
+pub fn find_in_cargo_metadata(directory: &Path) -> Result<Option<CargoManifest>, Error> {
+    let mut in_package = false;
+
+    for directory in directory.ancestors() {
+        let path = directory.join("Cargo.toml");
+        if !path.is_file() {
+            continue;
+        }
+
+        let source = std::fs::read_to_string(&path)
+            .with_context(|| format!("failed to read {}", path.display()))?;
+        let cargo_toml: Value = toml::from_str(&source)
+            .with_context(|| format!("failed to parse {}", path.display()))?;
+
+        for section in ["package", "workspace"] {
+            if section == "package" && in_package {
+                continue;
+            }
+
+            let pointer = format!("/{section}/metadata/{METADATA_KEY}");
+            if let Some(manifest) = cargo_toml.pointer(&pointer) {
+                return Ok(Some(CargoManifest {
+                    path,
+                    table: format!("{section}.metadata.{METADATA_KEY}"),
+                    manifest: manifest.clone(),
+                }));
+            }
+        }
+
+        // Stop at the root of the workspace, or continue to search for it
+        if cargo_toml.get("workspace").is_some() {
+            break;
+        }
+        in_package = true;
+    }
+
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    use super::*;
+
+    const PACKAGE: &str = indoc! {r#"
+        [package]
+        name = "octoapp"
+
+        [package.metadata.github-app]
+        name = "Octoapp"
+        url = "https://example.com"
+    "#};
+
+    const WORKSPACE: &str = indoc! {r#"
+        [workspace]
+        members = ["octoapp"]
+
+        [workspace.metadata.github-app]
+        name = "Workspace"
+        url = "https://example.com"
+    "#};
+
+    #[test]
+    fn find_in_cargo_metadata_reads_package_metadata() {
+        let directory = TempDir::new().unwrap();
+        std::fs::write(directory.path().join("Cargo.toml"), WORKSPACE).unwrap();
+        std::fs::create_dir_all(directory.path().join("octoapp/src")).unwrap();
+        std::fs::write(directory.path().join("octoapp/Cargo.toml"), PACKAGE).unwrap();
+
+        let found = find_in_cargo_metadata(&directory.path().join("octoapp/src"))
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(directory.path().join("octoapp/Cargo.toml"), found.path);
+        assert_eq!("package.metadata.github-app", found.table);
+        assert_eq!(
+            json!({"name": "Octoapp", "url": "https://example.com"}),
+            found.manifest
+        );
+    }
+
+    #[test]
+    fn find_in_cargo_metadata_falls_back_to_workspace_metadata() {
+        let directory = TempDir::new().unwrap();
+        std::fs::write(directory.path().join("Cargo.toml"), WORKSPACE).unwrap();
+        std::fs::create_dir_all(directory.path().join("octoapp")).unwrap();
+        std::fs::write(
+            directory.path().join("octoapp/Cargo.toml"),
+            "[package]\nname = \"octoapp\"\n",
+        )
+        .unwrap();
+
+        let found = find_in_cargo_metadata(&directory.path().join("octoapp"))
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(directory.path().join("Cargo.toml"), found.path);
+        assert_eq!("workspace.metadata.github-app", found.table);
+    }
+
+    #[test]
+    fn find_in_cargo_metadata_returns_none_without_metadata() {
+        let directory = TempDir::new().unwrap();
+        std::fs::write(
+            directory.path().join("Cargo.toml"),
+            "[workspace]\nmembers = []\n",
+        )
+        .unwrap();
+
+        let found = find_in_cargo_metadata(directory.path()).unwrap();
+
+        assert_eq!(None, found);
+    }
+
+    #[test]
+    fn trait_send() {
+        fn assert_send<T: Send>() {}
+        assert_send::<CargoManifest>();
+    }
+
+    #[test]
+    fn trait_sync() {
+        fn assert_sync<T: Sync>() {}
+        assert_sync::<CargoManifest>();
+    }
+
+    #[test]
+    fn trait_unpin() {
+        fn assert_unpin<T: Unpin>() {}
+        assert_unpin::<CargoManifest>();
+    }
+}
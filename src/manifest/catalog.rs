@@ -0,0 +1,408 @@
+//! Catalog of the permissions and webhook events of GitHub Apps
+//!
+//! GitHub documents the permissions that an app can request and the webhook events it can subscribe
+//! to at <https://docs.github.com/en/rest/overview/permissions-required-for-github-apps> and
+//! <https://docs.github.com/en/webhooks/webhook-events-and-payloads>.
+
+use std::cmp::Ordering;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+use anyhow::{anyhow, Error};
+use serde::{Deserialize, Serialize};
+
+/// Generate the enum of permissions and the access levels that can be requested for them
+macro_rules! permissions {
+    ($($variant:ident => $name:literal: [$($level:ident),+],)+) => {
+        /// A permission that a GitHub App can request
+        ///
+        /// Permissions are ordered by their name, so that the permissions in a manifest are always
+        /// serialized in the same order.
+        #[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Deserialize, Serialize)]
+        pub enum Permission {
+            $(
+                #[doc = concat!("The `", $name, "` permission")]
+                #[serde(rename = $name)]
+                $variant,
+            )+
+        }
+
+        impl Permission {
+            /// All permissions that a GitHub App can request
+            pub const ALL: &'static [Permission] = &[$(Permission::$variant),+];
+
+            /// Return the name of the permission
+            pub fn as_str(&self) -> &'static str {
+                match self {
+                    $(Permission::$variant => $name,)+
+                }
+            }
+
+            /// Return the access levels that can be requested for the permission
+            pub fn access_levels(&self) -> &'static [AccessLevel] {
+                match self {
+                    $(Permission::$variant => &[$(AccessLevel::$level),+],)+
+                }
+            }
+        }
+    };
+}
+
+/// Generate the enum of webhook events and the permissions that are required to receive them
+macro_rules! events {
+    ($($variant:ident => $name:literal: [$($permission:ident),*],)+) => {
+        /// A webhook event to which a GitHub App can subscribe
+        ///
+        /// GitHub regularly adds new events, so events that are not in the catalog are kept as
+        /// [`Event::Other`] instead of being rejected.
+        #[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Deserialize, Serialize)]
+        #[serde(from = "String", into = "String")]
+        pub enum Event {
+            $(
+                #[doc = concat!("The `", $name, "` event")]
+                $variant,
+            )+
+
+            /// An event that is not in the catalog
+            Other(String),
+        }
+
+        impl Event {
+            /// All events in the catalog
+            pub const ALL: &'static [Event] = &[$(Event::$variant),+];
+
+            /// Return the name of the event
+            pub fn as_str(&self) -> &str {
+                match self {
+                    $(Event::$variant => $name,)+
+                    Event::Other(name) => name,
+                }
+            }
+
+            /// Return the permissions of which one is required to receive the event
+            ///
+            /// Events that do not require any permission return an empty list. Events that are not
+            /// in the catalog return `None`.
+            pub fn required_permissions(&self) -> Option<&'static [Permission]> {
+                match self {
+                    $(Event::$variant => Some(&[$(Permission::$permission),*]),)+
+                    Event::Other(_) => None,
+                }
+            }
+        }
+
+        impl From<&str> for Event {
+            fn from(name: &str) -> Self {
+                match name {
+                    $($name => Event::$variant,)+
+                    name => Event::Other(name.to_string()),
+                }
+            }
+        }
+    };
+}
+
+permissions! {
+    // Repository permissions
+    Actions => "actions": [Read, Write],
+    Administration => "administration": [Read, Write],
+    Attestations => "attestations": [Read, Write],
+    Checks => "checks": [Read, Write],
+    Codespaces => "codespaces": [Read, Write],
+    Contents => "contents": [Read, Write],
+    DependabotSecrets => "dependabot_secrets": [Read, Write],
+    Deployments => "deployments": [Read, Write],
+    Discussions => "discussions": [Read, Write],
+    Environments => "environments": [Read, Write],
+    Issues => "issues": [Read, Write],
+    MergeQueues => "merge_queues": [Read, Write],
+    Metadata => "metadata": [Read, Write],
+    Packages => "packages": [Read, Write],
+    Pages => "pages": [Read, Write],
+    PullRequests => "pull_requests": [Read, Write],
+    RepositoryAdvisories => "repository_advisories": [Read, Write],
+    RepositoryCustomProperties => "repository_custom_properties": [Read, Write],
+    RepositoryHooks => "repository_hooks": [Read, Write],
+    RepositoryProjects => "repository_projects": [Read, Write, Admin],
+    SecretScanningAlerts => "secret_scanning_alerts": [Read, Write],
+    Secrets => "secrets": [Read, Write],
+    SecurityEvents => "security_events": [Read, Write],
+    SingleFile => "single_file": [Read, Write],
+    Statuses => "statuses": [Read, Write],
+    VulnerabilityAlerts => "vulnerability_alerts": [Read, Write],
+    Workflows => "workflows": [Write],
+    // Organization permissions
+    Members => "members": [Read, Write],
+    OrganizationAdministration => "organization_administration": [Read, Write],
+    OrganizationAnnouncementBanners => "organization_announcement_banners": [Read, Write],
+    OrganizationCopilotSeatManagement => "organization_copilot_seat_management": [Write],
+    OrganizationCustomOrgRoles => "organization_custom_org_roles": [Read, Write],
+    OrganizationCustomProperties => "organization_custom_properties": [Read, Write, Admin],
+    OrganizationCustomRoles => "organization_custom_roles": [Read, Write],
+    OrganizationEvents => "organization_events": [Read],
+    OrganizationHooks => "organization_hooks": [Read, Write],
+    OrganizationPackages => "organization_packages": [Read, Write],
+    OrganizationPersonalAccessTokenRequests => "organization_personal_access_token_requests":
+        [Read, Write],
+    OrganizationPersonalAccessTokens => "organization_personal_access_tokens": [Read, Write],
+    OrganizationPlan => "organization_plan": [Read],
+    OrganizationProjects => "organization_projects": [Read, Write, Admin],
+    OrganizationSecrets => "organization_secrets": [Read, Write],
+    OrganizationSelfHostedRunners => "organization_self_hosted_runners": [Read, Write],
+    OrganizationUserBlocking => "organization_user_blocking": [Read, Write],
+    TeamDiscussions => "team_discussions": [Read, Write],
+    // Account permissions
+    EmailAddresses => "email_addresses": [Read, Write],
+    Followers => "followers": [Read, Write],
+    GitSshKeys => "git_ssh_keys": [Read, Write],
+    GpgKeys => "gpg_keys": [Read, Write],
+    InteractionLimits => "interaction_limits": [Read, Write],
+    Profile => "profile": [Write],
+    Starring => "starring": [Read, Write],
+}
+
+events! {
+    BranchProtectionConfiguration => "branch_protection_configuration": [Administration],
+    BranchProtectionRule => "branch_protection_rule": [Administration],
+    CheckRun => "check_run": [Checks],
+    CheckSuite => "check_suite": [Checks],
+    CodeScanningAlert => "code_scanning_alert": [SecurityEvents],
+    CommitComment => "commit_comment": [Contents],
+    Create => "create": [Contents],
+    CustomPropertyValues => "custom_property_values": [RepositoryCustomProperties],
+    Delete => "delete": [Contents],
+    DependabotAlert => "dependabot_alert": [VulnerabilityAlerts],
+    DeployKey => "deploy_key": [Administration],
+    Deployment => "deployment": [Deployments],
+    DeploymentProtectionRule => "deployment_protection_rule": [Deployments],
+    DeploymentReview => "deployment_review": [Actions],
+    DeploymentStatus => "deployment_status": [Deployments],
+    Discussion => "discussion": [Discussions],
+    DiscussionComment => "discussion_comment": [Discussions],
+    Fork => "fork": [Contents],
+    Gollum => "gollum": [Contents],
+    IssueComment => "issue_comment": [Issues, PullRequests],
+    Issues => "issues": [Issues],
+    Label => "label": [Metadata],
+    Member => "member": [Members],
+    Membership => "membership": [Members],
+    MergeGroup => "merge_group": [MergeQueues],
+    Meta => "meta": [],
+    Milestone => "milestone": [Issues, PullRequests],
+    OrgBlock => "org_block": [OrganizationUserBlocking],
+    Organization => "organization": [Members],
+    Package => "package": [Packages],
+    PageBuild => "page_build": [Pages],
+    PersonalAccessTokenRequest => "personal_access_token_request":
+        [OrganizationPersonalAccessTokenRequests],
+    Project => "project": [RepositoryProjects, OrganizationProjects],
+    ProjectCard => "project_card": [RepositoryProjects, OrganizationProjects],
+    ProjectColumn => "project_column": [RepositoryProjects, OrganizationProjects],
+    ProjectsV2 => "projects_v2": [OrganizationProjects],
+    ProjectsV2Item => "projects_v2_item": [OrganizationProjects],
+    Public => "public": [Metadata],
+    PullRequest => "pull_request": [PullRequests],
+    PullRequestReview => "pull_request_review": [PullRequests],
+    PullRequestReviewComment => "pull_request_review_comment": [PullRequests],
+    PullRequestReviewThread => "pull_request_review_thread": [PullRequests],
+    Push => "push": [Contents],
+    RegistryPackage => "registry_package": [Packages],
+    Release => "release": [Contents],
+    Repository => "repository": [Metadata],
+    RepositoryAdvisory => "repository_advisory": [RepositoryAdvisories],
+    RepositoryDispatch => "repository_dispatch": [Contents],
+    RepositoryRuleset => "repository_ruleset": [Administration],
+    SecretScanningAlert => "secret_scanning_alert": [SecretScanningAlerts],
+    SecretScanningAlertLocation => "secret_scanning_alert_location": [SecretScanningAlerts],
+    SecurityAdvisory => "security_advisory": [],
+    SecurityAndAnalysis => "security_and_analysis": [Administration],
+    Star => "star": [Metadata],
+    Status => "status": [Statuses],
+    Team => "team": [Members],
+    TeamAdd => "team_add": [Members],
+    Watch => "watch": [Metadata],
+    WorkflowDispatch => "workflow_dispatch": [Contents],
+    WorkflowJob => "workflow_job": [Actions],
+    WorkflowRun => "workflow_run": [Actions],
+}
+
+/// The access level of a permission
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AccessLevel {
+    /// Read-only access
+    Read,
+
+    /// Read and write access
+    Write,
+
+    /// Administrative access
+    Admin,
+}
+
+impl AccessLevel {
+    /// Return the name of the access level
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AccessLevel::Read => "read",
+            AccessLevel::Write => "write",
+            AccessLevel::Admin => "admin",
+        }
+    }
+}
+
+impl Display for AccessLevel {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl FromStr for AccessLevel {
+    type Err = Error;
+
+    fn from_str(access_level: &str) -> Result<Self, Self::Err> {
+        match access_level {
+            "read" => Ok(AccessLevel::Read),
+            "write" => Ok(AccessLevel::Write),
+            "admin" => Ok(AccessLevel::Admin),
+            _ => Err(anyhow!("unknown access level `{access_level}`")),
+        }
+    }
+}
+
+impl Display for Permission {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl FromStr for Permission {
+    type Err = Error;
+
+    fn from_str(permission: &str) -> Result<Self, Self::Err> {
+        Permission::ALL
+            .iter()
+            .find(|candidate| candidate.as_str() == permission)
+            .copied()
+            .ok_or_else(|| anyhow!("unknown permission `{permission}`"))
+    }
+}
+
+impl Ord for Permission {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.as_str().cmp(other.as_str())
+    }
+}
+
+impl PartialOrd for Permission {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Event {
+    /// Return true if the event is not in the catalog
+    pub fn is_unknown(&self) -> bool {
+        matches!(self, Event::Other(_))
+    }
+}
+
+impl Display for Event {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl From<String> for Event {
+    fn from(name: String) -> Self {
+        Event::from(name.as_str())
+    }
+}
+
+impl From<Event> for String {
+    fn from(event: Event) -> Self {
+        event.as_str().to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn permission_from_str_round_trips() {
+        for permission in Permission::ALL {
+            assert_eq!(*permission, permission.as_str().parse().unwrap());
+        }
+    }
+
+    #[test]
+    fn permission_from_str_errors_on_unknown_permission() {
+        let error = "isues".parse::<Permission>().unwrap_err();
+
+        assert_eq!("unknown permission `isues`", error.to_string());
+    }
+
+    #[test]
+    fn permission_serializes_as_name() {
+        let json = serde_json::to_string(&Permission::PullRequests).unwrap();
+
+        assert_eq!(r#""pull_requests""#, json);
+    }
+
+    #[test]
+    fn permissions_are_ordered_by_name() {
+        assert!(Permission::Workflows > Permission::Members);
+    }
+
+    #[test]
+    fn access_level_deserializes_from_lowercase() {
+        let level: AccessLevel = serde_json::from_str(r#""admin""#).unwrap();
+
+        assert_eq!(AccessLevel::Admin, level);
+    }
+
+    #[test]
+    fn event_from_str_round_trips() {
+        for event in Event::ALL {
+            assert_eq!(*event, Event::from(event.as_str()));
+        }
+    }
+
+    #[test]
+    fn event_keeps_unknown_events() {
+        let event: Event = serde_json::from_str(r#""future_event""#).unwrap();
+
+        assert_eq!(Event::Other("future_event".into()), event);
+        assert_eq!(r#""future_event""#, serde_json::to_string(&event).unwrap());
+    }
+
+    #[test]
+    fn events_are_sorted() {
+        let events: Vec<&str> = Event::ALL.iter().map(Event::as_str).collect();
+        let mut sorted_events = events.clone();
+        sorted_events.sort_unstable();
+
+        assert_eq!(sorted_events, events);
+    }
+
+    #[test]
+    fn trait_send() {
+        fn assert_send<T: Send>() {}
+        assert_send::<Event>();
+    }
+
+    #[test]
+    fn trait_sync() {
+        fn assert_sync<T: Sync>() {}
+        assert_sync::<Event>();
+    }
+
+    #[test]
+    fn trait_unpin() {
+        fn assert_unpin<T: Unpin>() {}
+        assert_unpin::<Event>();
+    }
+}
@@ -0,0 +1,539 @@
+//! Validate a manifest beyond the structure that is enforced by deserialization
+
+use std::fmt::{Display, Formatter};
+
+use getset::{CopyGetters, Getters};
+use serde::Serialize;
+use serde_json::{Map, Value};
+use url::Url;
+
+use super::position::{escape_pointer_token, Position, PositionIndex};
+use super::{has_placeholders, strip_comments, AccessLevel, Event, Format, Manifest, Permission};
+
+/// The maximum number of callback URLs that GitHub accepts
+const MAX_CALLBACK_URLS: usize = 10;
+
+/// The fields of the manifest that must contain a URL, as JSON pointers
+const URL_FIELDS: [&str; 4] = [
+    "/url",
+    "/redirect_url",
+    "/setup_url",
+    "/hook_attributes/url",
+];
+
+/// How severe a problem in a manifest is
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    /// The manifest is invalid
+    Error,
+
+    /// The manifest is valid, but probably not what the user intended
+    Warning,
+}
+
+/// A problem in a manifest
+///
+/// Problems are reported with the position in the manifest at which they were found, if the
+/// position is known. With `--json`, they are serialized with the line and column of the position.
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, CopyGetters, Getters, Serialize)]
+pub struct Diagnostic {
+    /// The position of the problem in the manifest
+    #[getset(get_copy = "pub")]
+    #[serde(flatten)]
+    position: Option<Position>,
+
+    /// The description of the problem
+    #[getset(get = "pub")]
+    message: String,
+
+    /// How severe the problem is
+    #[getset(get_copy = "pub")]
+    severity: Severity,
+}
+
+impl Diagnostic {
+    /// Create a new diagnostic for an error
+    fn new(position: Option<Position>, message: impl Into<String>) -> Self {
+        Self {
+            position,
+            message: message.into(),
+            severity: Severity::Error,
+        }
+    }
+
+    /// Create a new diagnostic for a warning
+    fn warning(position: Option<Position>, message: impl Into<String>) -> Self {
+        Self {
+            severity: Severity::Warning,
+            ..Self::new(position, message)
+        }
+    }
+
+    /// Create a diagnostic from an error that was returned by `serde_json`
+    ///
+    /// The position is removed from the error message, since it is reported separately.
+    fn from_serde(error: &serde_json::Error) -> Self {
+        let message = error.to_string();
+        let suffix = format!(" at line {} column {}", error.line(), error.column());
+        let message = message.strip_suffix(&suffix).unwrap_or(&message);
+
+        Self::new(Some(Position::new(error.line(), error.column())), message)
+    }
+}
+
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if let Some(position) = self.position {
+            write!(f, "{position}: ")?;
+        }
+        if self.severity == Severity::Warning {
+            write!(f, "warning: ")?;
+        }
+
+        write!(f, "{}", self.message)
+    }
+}
+
+/// Check a manifest for problems
+///
+/// The manifest is checked for invalid URLs, too many callback URLs, unknown permissions and
+/// access levels, unknown webhook events, and events whose required permission is missing. If none
+/// of these problems are found, the manifest is deserialized to find structural problems like
+/// missing fields. Fields that are unknown to this tool are reported as warnings, since they are
+/// often typos. The manifest is valid if the list contains no errors.
+///
+/// Positions are only reported for JSON manifests. Problems in YAML and TOML manifests are reported
+/// without a position, except for syntax errors.
+pub fn check(source: &str, format: Format) -> Vec<Diagnostic> {
+    match format {
+        Format::Json => check_json(&strip_comments(source)),
+        _ => match format.parse(source) {
+            Ok(manifest) => check_value(manifest),
+            Err(error) => vec![Diagnostic::new(None, format!("{error:#}"))],
+        },
+    }
+}
+
+/// Check a manifest that has already been parsed
+///
+/// The positions of the values are unknown, so problems are reported without a position.
+pub fn check_value(manifest: Value) -> Vec<Diagnostic> {
+    check_parsed(manifest, &PositionIndex::default(), |manifest| {
+        serde_json::from_value::<Manifest>(manifest)
+            .map_err(|error| Diagnostic::new(None, error.to_string()))
+    })
+}
+
+/// Check a JSON manifest whose comments have been stripped
+fn check_json(source: &str) -> Vec<Diagnostic> {
+    let manifest: Value = match serde_json::from_str(source) {
+        Ok(manifest) => manifest,
+        Err(error) => return vec![Diagnostic::from_serde(&error)],
+    };
+
+    check_parsed(manifest, &PositionIndex::new(source), |_| {
+        serde_json::from_str::<Manifest>(source).map_err(|error| Diagnostic::from_serde(&error))
+    })
+}
+
+/// Check a parsed manifest
+///
+/// The manifest is only deserialized when no other problems have been found, since the problems
+/// found by the checks are reported with a better description and position.
+fn check_parsed(
+    manifest: Value,
+    index: &PositionIndex,
+    deserialize: impl FnOnce(Value) -> Result<Manifest, Diagnostic>,
+) -> Vec<Diagnostic> {
+    let Some(object) = manifest.as_object() else {
+        return vec![Diagnostic::new(
+            index.value(""),
+            "manifest must be an object",
+        )];
+    };
+
+    let mut diagnostics = Vec::new();
+    check_urls(&manifest, index, &mut diagnostics);
+    check_permissions(object, index, &mut diagnostics);
+    check_events(object, index, &mut diagnostics);
+
+    if diagnostics.is_empty() {
+        match deserialize(manifest) {
+            Ok(manifest) => check_unknown_fields(&manifest, index, &mut diagnostics),
+            Err(diagnostic) => diagnostics.push(diagnostic),
+        }
+    }
+
+    // Report the problems in the order in which they appear in the manifest
+    diagnostics.sort();
+
+    diagnostics
+}
+
+/// Warn about fields that are unknown to this tool
+fn check_unknown_fields(
+    manifest: &Manifest,
+    index: &PositionIndex,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    let hook_fields = manifest
+        .hook_attributes()
+        .iter()
+        .flat_map(|hook_attributes| hook_attributes.unknown_fields().keys())
+        .map(|field| ("hook_attributes", field));
+    let fields = manifest
+        .unknown_fields()
+        .keys()
+        .map(|field| ("", field))
+        .chain(hook_fields);
+
+    for (parent, field) in fields {
+        let (pointer, name) = match parent {
+            "" => (format!("/{}", escape_pointer_token(field)), field.clone()),
+            parent => (
+                format!("/{parent}/{}", escape_pointer_token(field)),
+                format!("{parent}.{field}"),
+            ),
+        };
+
+        diagnostics.push(Diagnostic::warning(
+            index.key(&pointer),
+            format!("unknown field `{name}` is sent to GitHub unchanged"),
+        ));
+    }
+}
+
+/// Check that all URL fields contain absolute HTTP URLs
+fn check_urls(manifest: &Value, index: &PositionIndex, diagnostics: &mut Vec<Diagnostic>) {
+    let mut pointers: Vec<String> = URL_FIELDS.iter().map(ToString::to_string).collect();
+
+    if let Some(callback_urls) = manifest.pointer("/callback_urls").and_then(Value::as_array) {
+        if callback_urls.len() > MAX_CALLBACK_URLS {
+            diagnostics.push(Diagnostic::new(
+                index.value("/callback_urls"),
+                format!(
+                    "at most {MAX_CALLBACK_URLS} callback URLs are allowed, found {}",
+                    callback_urls.len()
+                ),
+            ));
+        }
+
+        pointers.extend((0..callback_urls.len()).map(|item| format!("/callback_urls/{item}")));
+    }
+
+    for pointer in pointers {
+        let Some(url) = manifest.pointer(&pointer).and_then(Value::as_str) else {
+            continue;
+        };
+
+        // Placeholders are only replaced when the app is registered
+        if has_placeholders(url) {
+            continue;
+        }
+
+        let problem = match Url::parse(url) {
+            Ok(url) if matches!(url.scheme(), "http" | "https") => continue,
+            Ok(url) => format!("unsupported scheme `{}`", url.scheme()),
+            Err(error) => error.to_string(),
+        };
+
+        diagnostics.push(Diagnostic::new(
+            index.value(&pointer),
+            format!(
+                "invalid URL `{url}` in {}: {problem}",
+                pointer.trim_start_matches('/').replace('/', ".")
+            ),
+        ));
+    }
+}
+
+/// Check that all permissions and access levels are known
+fn check_permissions(
+    manifest: &Map<String, Value>,
+    index: &PositionIndex,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    let Some(permissions) = manifest
+        .get("default_permissions")
+        .and_then(Value::as_object)
+    else {
+        return;
+    };
+
+    for (permission, access) in permissions {
+        let pointer = format!("/default_permissions/{}", escape_pointer_token(permission));
+
+        let permission = match permission.parse::<Permission>() {
+            Ok(permission) => permission,
+            Err(error) => {
+                diagnostics.push(Diagnostic::new(index.key(&pointer), error.to_string()));
+                continue;
+            }
+        };
+
+        let levels = permission.access_levels();
+        if !access
+            .as_str()
+            .and_then(|access| access.parse::<AccessLevel>().ok())
+            .is_some_and(|access| levels.contains(&access))
+        {
+            let levels: Vec<&str> = levels.iter().map(AccessLevel::as_str).collect();
+
+            diagnostics.push(Diagnostic::new(
+                index.value(&pointer),
+                format!(
+                    "invalid access level {access} for permission `{permission}`, expected one of {}",
+                    levels.join(", ")
+                ),
+            ));
+        }
+    }
+}
+
+/// Check that all events are known and that their required permissions are requested
+fn check_events(
+    manifest: &Map<String, Value>,
+    index: &PositionIndex,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    let Some(events) = manifest.get("default_events").and_then(Value::as_array) else {
+        return;
+    };
+
+    let permissions = manifest
+        .get("default_permissions")
+        .and_then(Value::as_object);
+
+    for (item, event) in events.iter().enumerate() {
+        let Some(event) = event.as_str() else {
+            continue;
+        };
+        let position = index.value(&format!("/default_events/{item}"));
+
+        let event = Event::from(event);
+        let Some(required) = event.required_permissions() else {
+            diagnostics.push(Diagnostic::new(
+                position,
+                format!("unknown webhook event `{event}`"),
+            ));
+            continue;
+        };
+
+        let has_permission = required.is_empty()
+            || required
+                .iter()
+                .any(|permission| permissions.is_some_and(|p| p.contains_key(permission.as_str())));
+
+        if !has_permission {
+            let required: Vec<String> = required
+                .iter()
+                .map(|permission| format!("`{permission}: read`"))
+                .collect();
+
+            diagnostics.push(Diagnostic::new(
+                position,
+                format!(
+                    "webhook event `{event}` requires the permission {}",
+                    required.join(" or ")
+                ),
+            ));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn messages(source: &str) -> Vec<String> {
+        check(source, Format::Json)
+            .iter()
+            .map(ToString::to_string)
+            .collect()
+    }
+
+    #[test]
+    fn check_accepts_valid_manifest() {
+        let source = indoc! {r#"
+            {
+              "url": "https://www.example.com",
+              "hook_attributes": { "url": "https://smee.io/otters" },
+              "default_permissions": { "issues": "write", "pull_requests": "read" },
+              "default_events": ["issues", "issue_comment", "meta"]
+            }
+        "#};
+
+        assert!(check(source, Format::Json).is_empty());
+    }
+
+    #[test]
+    fn check_reports_invalid_urls() {
+        let source = indoc! {r#"
+            {
+              "url": "www.example.com",
+              "hook_attributes": { "url": "ftp://example.com" }
+            }
+        "#};
+
+        assert_eq!(
+            vec![
+                "2:10: invalid URL `www.example.com` in url: relative URL without a base",
+                "3:31: invalid URL `ftp://example.com` in hook_attributes.url: unsupported scheme `ftp`",
+            ],
+            messages(source)
+        );
+    }
+
+    #[test]
+    fn check_warns_about_unknown_fields() {
+        let source = indoc! {r#"
+            {
+              "$schema": "https://example.com/manifest.schema.json",
+              "nmae": "octoapp",
+              "url": "https://www.example.com",
+              "hook_attributes": { "url": "https://example.com", "secert": "otters" }
+            }
+        "#};
+
+        let diagnostics = check(source, Format::Json);
+
+        assert_eq!(
+            vec![
+                "3:3: warning: unknown field `nmae` is sent to GitHub unchanged",
+                "5:54: warning: unknown field `hook_attributes.secert` is sent to GitHub unchanged",
+            ],
+            diagnostics
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+        );
+        assert!(diagnostics
+            .iter()
+            .all(|diagnostic| diagnostic.severity() == Severity::Warning));
+    }
+
+    #[test]
+    fn check_skips_urls_with_placeholders() {
+        let source = r#"{"url": "http://localhost:{{port}}", "setup_url": "${SETUP_URL}"}"#;
+
+        assert!(check(source, Format::Json).is_empty());
+    }
+
+    #[test]
+    fn check_reports_too_many_callback_urls() {
+        let urls = [r#""https://example.com""#; 11].join(", ");
+        let source = format!(r#"{{"url": "https://example.com", "callback_urls": [{urls}]}}"#);
+
+        assert_eq!(
+            vec!["1:49: at most 10 callback URLs are allowed, found 11"],
+            messages(&source)
+        );
+    }
+
+    #[test]
+    fn check_reports_unknown_permissions_and_access_levels() {
+        let source = indoc! {r#"
+            {
+              "url": "https://www.example.com",
+              "default_permissions": {
+                "isues": "write",
+                "issues": "wirte"
+              }
+            }
+        "#};
+
+        assert_eq!(
+            vec![
+                "4:5: unknown permission `isues`",
+                r#"5:15: invalid access level "wirte" for permission `issues`, expected one of read, write"#,
+            ],
+            messages(source)
+        );
+    }
+
+    #[test]
+    fn check_reports_unknown_events_and_missing_permissions() {
+        let source = indoc! {r#"
+            {
+              "url": "https://www.example.com",
+              "default_events": ["isues", "issues", "issue_comment"]
+            }
+        "#};
+
+        assert_eq!(
+            vec![
+                "3:22: unknown webhook event `isues`",
+                "3:31: webhook event `issues` requires the permission `issues: read`",
+                "3:41: webhook event `issue_comment` requires the permission `issues: read` or `pull_requests: read`",
+            ],
+            messages(source)
+        );
+    }
+
+    #[test]
+    fn check_reports_missing_fields() {
+        assert_eq!(
+            vec!["1:19: missing field `url`"],
+            messages(r#"{"name": "Octoapp"}"#)
+        );
+    }
+
+    #[test]
+    fn check_reports_syntax_errors() {
+        assert_eq!(
+            vec!["1:13: EOF while parsing a string"],
+            messages(r#"{"url": "http"#)
+        );
+    }
+
+    #[test]
+    fn check_ignores_comments() {
+        let source = indoc! {r#"
+            {
+              // The homepage of the app
+              "url": "https://www.example.com",
+              "default_permissions": { "isues": "write" }
+            }
+        "#};
+
+        assert_eq!(vec!["4:28: unknown permission `isues`"], messages(source));
+    }
+
+    #[test]
+    fn check_reports_problems_in_yaml_without_position() {
+        let source = indoc! {r#"
+            url: https://www.example.com
+            default_permissions:
+              isues: write
+        "#};
+
+        let diagnostics: Vec<String> = check(source, Format::Yaml)
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+
+        assert_eq!(vec!["unknown permission `isues`"], diagnostics);
+    }
+
+    #[test]
+    fn trait_send() {
+        fn assert_send<T: Send>() {}
+        assert_send::<Diagnostic>();
+    }
+
+    #[test]
+    fn trait_sync() {
+        fn assert_sync<T: Sync>() {}
+        assert_sync::<Diagnostic>();
+    }
+
+    #[test]
+    fn trait_unpin() {
+        fn assert_unpin<T: Unpin>() {}
+        assert_unpin::<Diagnostic>();
+    }
+}
@@ -0,0 +1,134 @@
+//! Command to work with manifest files
+
+use anyhow::{Context, Error};
+use async_trait::async_trait;
+use serde_json::json;
+
+use crate::cli::Args;
+use crate::error::{CliError, Details};
+use crate::Execute;
+
+use super::check::{check, check_value, Severity};
+use super::{
+    find_in_current_directory, has_base_manifest, manifest_schema, read_manifest, Format,
+    ManifestArgs, ManifestSubcommand,
+};
+
+/// Work with manifest files
+///
+/// This command checks manifest files for problems that GitHub would otherwise only report when
+/// the manifest is submitted to register a new app, and prints the JSON Schema of the format.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct ManifestCommand<'a> {
+    /// The arguments for the command
+    args: &'a ManifestArgs,
+}
+
+impl<'a> ManifestCommand<'a> {
+    /// Create a new instance of the command
+    pub fn new(args: &'a ManifestArgs) -> Self {
+        Self { args }
+    }
+}
+
+#[async_trait]
+impl<'a> Execute for ManifestCommand<'a> {
+    async fn execute(&self, global_args: &Args) -> Result<(), Error> {
+        let output = global_args.output();
+
+        match self.args.command() {
+            ManifestSubcommand::Check { path, profile } => {
+                let (location, diagnostics) = match path {
+                    // Positions are only known in a single file, so profiles are checked as a whole
+                    Some(path) if profile.is_some() => (
+                        format!(
+                            "{}[{}]",
+                            path.display(),
+                            profile.as_deref().unwrap_or_default()
+                        ),
+                        check_value(read_manifest(path, profile.as_deref())?),
+                    ),
+                    Some(path) => {
+                        let source =
+                            std::fs::read_to_string(path).context(CliError::ManifestUnreadable)?;
+                        let format = Format::from_path(path);
+
+                        // Manifests that extend another manifest are checked with their base
+                        let extends = format
+                            .parse(&source)
+                            .is_ok_and(|manifest| has_base_manifest(&manifest));
+                        let diagnostics = match extends {
+                            true => check_value(read_manifest(path, None)?),
+                            false => check(&source, format),
+                        };
+
+                        (path.display().to_string(), diagnostics)
+                    }
+                    None => {
+                        let found = find_in_current_directory()?;
+
+                        (
+                            format!("{}[{}]", found.path().display(), found.table()),
+                            check_value(found.manifest().clone()),
+                        )
+                    }
+                };
+
+                for diagnostic in &diagnostics {
+                    output.message(format!("{location}:{diagnostic}"));
+                }
+
+                let errors = diagnostics
+                    .iter()
+                    .filter(|diagnostic| diagnostic.severity() == Severity::Error)
+                    .count();
+                if errors > 0 {
+                    let details = Details {
+                        message: format!("found {errors} problem(s) in {location}"),
+                        details: json!({ "location": location, "diagnostics": diagnostics }),
+                    };
+                    return Err(
+                        Error::new(details).context(CliError::InvalidManifest(format!(
+                            "{location} is not a valid manifest"
+                        ))),
+                    );
+                }
+
+                output.message(format!("{location} is a valid manifest"));
+                output.result(&json!({
+                    "location": location,
+                    "valid": true,
+                    "diagnostics": diagnostics,
+                }))?;
+            }
+            ManifestSubcommand::Schema => {
+                println!("{}", serde_json::to_string_pretty(&manifest_schema())?);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn trait_send() {
+        fn assert_send<T: Send>() {}
+        assert_send::<ManifestCommand>();
+    }
+
+    #[test]
+    fn trait_sync() {
+        fn assert_sync<T: Sync>() {}
+        assert_sync::<ManifestCommand>();
+    }
+
+    #[test]
+    fn trait_unpin() {
+        fn assert_unpin<T: Unpin>() {}
+        assert_unpin::<ManifestCommand>();
+    }
+}
@@ -0,0 +1,251 @@
+//! File formats in which manifests can be written
+
+use std::path::Path;
+
+use anyhow::{Context, Error};
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::error::CliError;
+
+/// The file format of a manifest
+///
+/// GitHub expects manifests to be JSON, but JSON does not allow comments that explain why an app
+/// needs a permission. Manifests can therefore also be written as YAML, TOML, or JSON with
+/// comments, and are converted to JSON before they are sent to GitHub.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
+pub enum Format {
+    /// JSON, optionally with `//` and `/* */` comments
+    #[default]
+    Json,
+
+    /// YAML
+    Yaml,
+
+    /// TOML
+    Toml,
+}
+
+impl Format {
+    /// Detect the format of a manifest from the extension of its file
+    ///
+    /// Files with an unknown or without an extension are treated as JSON.
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some("yaml" | "yml") => Format::Yaml,
+            Some("toml") => Format::Toml,
+            _ => Format::Json,
+        }
+    }
+
+    /// Parse the source of a manifest into a JSON value
+    pub fn parse(&self, source: &str) -> Result<Value, Error> {
+        match self {
+            Format::Json => serde_json::from_str(&strip_comments(source)).with_context(|| {
+                CliError::InvalidManifest("failed to parse manifest as JSON".into())
+            }),
+            Format::Yaml => serde_norway::from_str(source).with_context(|| {
+                CliError::InvalidManifest("failed to parse manifest as YAML".into())
+            }),
+            Format::Toml => toml::from_str(source).with_context(|| {
+                CliError::InvalidManifest("failed to parse manifest as TOML".into())
+            }),
+        }
+    }
+
+    /// Serialize a manifest in the format
+    pub fn serialize<T: Serialize>(&self, manifest: &T) -> Result<String, Error> {
+        match self {
+            Format::Json => serde_json::to_string_pretty(manifest)
+                .map(|json| format!("{json}\n"))
+                .context("failed to serialize manifest as JSON"),
+            Format::Yaml => {
+                serde_norway::to_string(manifest).context("failed to serialize manifest as YAML")
+            }
+            Format::Toml => {
+                toml::to_string_pretty(manifest).context("failed to serialize manifest as TOML")
+            }
+        }
+    }
+}
+
+/// Remove the comments from a JSON document
+///
+/// Comments are replaced with spaces, and line breaks inside block comments are kept, so that the
+/// positions of all values in the document stay the same.
+pub fn strip_comments(source: &str) -> String {
+    let mut stripped = String::with_capacity(source.len());
+    let mut chars = source.chars().peekable();
+    let mut in_string = false;
+
+    while let Some(char) = chars.next() {
+        if in_string {
+            stripped.push(char);
+            match char {
+                '\\' => stripped.extend(chars.next()),
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+
+        match (char, chars.peek()) {
+            ('"', _) => {
+                in_string = true;
+                stripped.push(char);
+            }
+            ('/', Some('/')) => {
+                while chars.peek().is_some_and(|char| *char != '\n') {
+                    chars.next();
+                    stripped.push(' ');
+                }
+                stripped.push(' ');
+            }
+            ('/', Some('*')) => {
+                chars.next();
+                stripped.push_str("  ");
+
+                let mut previous = None;
+                for char in chars.by_ref() {
+                    stripped.push(if char == '\n' { '\n' } else { ' ' });
+                    if previous == Some('*') && char == '/' {
+                        break;
+                    }
+                    previous = Some(char);
+                }
+            }
+            _ => stripped.push(char),
+        }
+    }
+
+    stripped
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn from_path_detects_format_by_extension() {
+        assert_eq!(Format::Yaml, Format::from_path(Path::new("manifest.yml")));
+        assert_eq!(Format::Yaml, Format::from_path(Path::new("manifest.yaml")));
+        assert_eq!(Format::Toml, Format::from_path(Path::new("manifest.toml")));
+        assert_eq!(Format::Json, Format::from_path(Path::new("manifest.jsonc")));
+        assert_eq!(Format::Json, Format::from_path(Path::new("manifest")));
+    }
+
+    #[test]
+    fn parse_json_with_comments() {
+        let source = indoc! {r#"
+            {
+              // The homepage of the app
+              "url": "https://example.com/*", /* not a comment in a string */
+              "default_permissions": {
+                /*
+                 * Labels are added to new issues
+                 */
+                "issues": "write"
+              }
+            }
+        "#};
+
+        let value = Format::Json.parse(source).unwrap();
+
+        assert_eq!(
+            json!({"url": "https://example.com/*", "default_permissions": {"issues": "write"}}),
+            value
+        );
+    }
+
+    #[test]
+    fn parse_yaml() {
+        let source = indoc! {r#"
+            url: https://example.com
+            default_permissions:
+              # Labels are added to new issues
+              issues: write
+            default_events:
+              - issues
+        "#};
+
+        let value = Format::Yaml.parse(source).unwrap();
+
+        assert_eq!(
+            json!({
+                "url": "https://example.com",
+                "default_permissions": {"issues": "write"},
+                "default_events": ["issues"]
+            }),
+            value
+        );
+    }
+
+    #[test]
+    fn parse_toml() {
+        let source = indoc! {r#"
+            url = "https://example.com"
+            default_events = ["issues"]
+
+            [default_permissions]
+            # Labels are added to new issues
+            issues = "write"
+        "#};
+
+        let value = Format::Toml.parse(source).unwrap();
+
+        assert_eq!(
+            json!({
+                "url": "https://example.com",
+                "default_permissions": {"issues": "write"},
+                "default_events": ["issues"]
+            }),
+            value
+        );
+    }
+
+    #[test]
+    fn serialize_round_trips() {
+        let manifest = json!({
+            "url": "https://example.com",
+            "default_permissions": {"issues": "write"},
+            "default_events": ["issues"]
+        });
+
+        for format in [Format::Json, Format::Yaml, Format::Toml] {
+            let source = format.serialize(&manifest).unwrap();
+
+            assert_eq!(manifest, format.parse(&source).unwrap());
+        }
+    }
+
+    #[test]
+    fn strip_comments_keeps_positions() {
+        let source = "{\n  /* a\n b */ \"url\": 1 // c\n}";
+
+        let stripped = strip_comments(source);
+
+        assert_eq!("{\n      \n      \"url\": 1     \n}", stripped);
+    }
+
+    #[test]
+    fn trait_send() {
+        fn assert_send<T: Send>() {}
+        assert_send::<Format>();
+    }
+
+    #[test]
+    fn trait_sync() {
+        fn assert_sync<T: Sync>() {}
+        assert_sync::<Format>();
+    }
+
+    #[test]
+    fn trait_unpin() {
+        fn assert_unpin<T: Unpin>() {}
+        assert_unpin::<Format>();
+    }
+}
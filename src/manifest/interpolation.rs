@@ -0,0 +1,367 @@
+//! Interpolation of environment variables and built-in values in manifests
+
+use std::process::Command;
+
+use anyhow::{anyhow, Context, Error};
+use getset::CopyGetters;
+use serde_json::Value;
+use typed_builder::TypedBuilder;
+
+use crate::error::CliError;
+
+use super::position::escape_pointer_token;
+
+/// The escaped form of a literal `${`
+const ESCAPED_DOLLAR: &str = "$${";
+
+/// The escaped form of a literal `{{`
+const ESCAPED_BRACES: &str = "{{{{";
+
+/// Values that are available to the built-in placeholders of a manifest
+///
+/// Most built-in values are read from the environment when they are used. Values that are only
+/// known while a command runs, e.g. the port of the local web server, are passed in here.
+///
+/// The port belongs to the temporary web server of the `register` command, which stops once the
+/// app is registered. It is meant for values that only matter during the registration, and must
+/// not be used for URLs that the app needs afterwards, e.g. its homepage or webhook URL.
+#[derive(
+    Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default, CopyGetters, TypedBuilder,
+)]
+pub struct Variables {
+    /// The port of the local web server
+    #[builder(default, setter(strip_option))]
+    #[getset(get_copy = "pub")]
+    port: Option<u16>,
+}
+
+impl Variables {
+    /// Return the value of a built-in placeholder
+    ///
+    /// The current user is read from `USER` on Unix and from `USERNAME` on Windows.
+    fn builtin(&self, name: &str) -> Result<String, Error> {
+        match name {
+            "user" => std::env::var("USER")
+                .or_else(|_| std::env::var("USERNAME"))
+                .context("failed to determine the current user"),
+            "hostname" => gethostname::gethostname()
+                .into_string()
+                .map_err(|_| anyhow!("hostname is not valid UTF-8")),
+            "git.branch" => git_branch(),
+            "port" => self
+                .port
+                .map(|port| port.to_string())
+                .ok_or_else(|| anyhow!("{{{{port}}}} is only available when registering an app")),
+            _ => Err(anyhow!("unknown placeholder {{{{{name}}}}}")),
+        }
+    }
+}
+
+/// Replace the placeholders in all strings of a manifest
+///
+/// Strings can contain environment variables as `${VAR}` or `${VAR:-default}`, and built-in values
+/// as `{{user}}`, `{{hostname}}`, `{{git.branch}}`, and `{{port}}`. The default of an environment
+/// variable is used when the variable is unset or empty. A literal `${` is written as `$${`, and a
+/// literal `{{` as `{{{{`, e.g. for template syntax in the description.
+pub fn interpolate(manifest: &mut Value, variables: &Variables) -> Result<(), Error> {
+    interpolate_value(manifest, "", variables)
+}
+
+/// Check whether a string contains placeholders that are replaced when the manifest is loaded
+pub fn has_placeholders(string: &str) -> bool {
+    let mut rest = string;
+
+    while let Some(start) = rest.find(['$', '{']) {
+        rest = &rest[start..];
+
+        if rest.starts_with(ESCAPED_DOLLAR) {
+            rest = &rest[ESCAPED_DOLLAR.len()..];
+        } else if rest.starts_with(ESCAPED_BRACES) {
+            rest = &rest[ESCAPED_BRACES.len()..];
+        } else if rest.starts_with("${") || rest.starts_with("{{") {
+            return true;
+        } else {
+            rest = &rest[1..];
+        }
+    }
+
+    false
+}
+
+/// Escape a string, so that it is kept unchanged when the manifest is loaded
+///
+/// This is the reverse of the interpolation for strings without placeholders, and is used for text
+/// that is not written by the user, e.g. the description of an exported app.
+pub fn escape_placeholders(string: &str) -> String {
+    let mut escaped = String::with_capacity(string.len());
+    let mut rest = string;
+
+    while let Some(start) = rest.find(['$', '{']) {
+        escaped.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        if let Some(after) = rest.strip_prefix("${") {
+            escaped.push_str(ESCAPED_DOLLAR);
+            rest = after;
+        } else if let Some(after) = rest.strip_prefix("{{") {
+            escaped.push_str(ESCAPED_BRACES);
+            rest = after;
+        } else {
+            escaped.push_str(&rest[..1]);
+            rest = &rest[1..];
+        }
+    }
+
+    escaped.push_str(rest);
+
+    escaped
+}
+
+/// Replace the placeholders in a value and its children
+fn interpolate_value(value: &mut Value, pointer: &str, variables: &Variables) -> Result<(), Error> {
+    match value {
+        Value::String(string) => {
+            *string = interpolate_string(string, variables).with_context(|| {
+                CliError::InvalidManifest(format!("failed to interpolate {pointer}"))
+            })?;
+        }
+        Value::Array(items) => {
+            for (index, item) in items.iter_mut().enumerate() {
+                interpolate_value(item, &format!("{pointer}/{index}"), variables)?;
+            }
+        }
+        Value::Object(object) => {
+            for (key, value) in object.iter_mut() {
+                let pointer = format!("{pointer}/{}", escape_pointer_token(key));
+                interpolate_value(value, &pointer, variables)?;
+            }
+        }
+        _ => {}
+    }
+
+    Ok(())
+}
+
+/// Replace the placeholders in a string
+fn interpolate_string(string: &str, variables: &Variables) -> Result<String, Error> {
+    let mut interpolated = String::with_capacity(string.len());
+    let mut rest = string;
+
+    while let Some(start) = rest.find(['$', '{']) {
+        interpolated.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        if let Some(escaped) = rest.strip_prefix(ESCAPED_DOLLAR) {
+            interpolated.push_str("${");
+            rest = escaped;
+        } else if let Some(escaped) = rest.strip_prefix(ESCAPED_BRACES) {
+            interpolated.push_str("{{");
+            rest = escaped;
+        } else if let Some(placeholder) = rest.strip_prefix("${") {
+            let end = placeholder
+                .find('}')
+                .ok_or_else(|| anyhow!("unterminated placeholder in `{string}`"))?;
+            interpolated.push_str(&environment_variable(&placeholder[..end])?);
+            rest = &placeholder[end + 1..];
+        } else if let Some(placeholder) = rest.strip_prefix("{{") {
+            let end = placeholder
+                .find("}}")
+                .ok_or_else(|| anyhow!("unterminated placeholder in `{string}`"))?;
+            interpolated.push_str(&variables.builtin(placeholder[..end].trim())?);
+            rest = &placeholder[end + 2..];
+        } else {
+            interpolated.push_str(&rest[..1]);
+            rest = &rest[1..];
+        }
+    }
+
+    interpolated.push_str(rest);
+
+    Ok(interpolated)
+}
+
+/// Return the value of an environment variable placeholder, e.g. `VAR` or `VAR:-default`
+fn environment_variable(placeholder: &str) -> Result<String, Error> {
+    let (name, default) = match placeholder.split_once(":-") {
+        Some((name, default)) => (name, Some(default)),
+        None => (placeholder, None),
+    };
+
+    match (std::env::var(name), default) {
+        (Ok(value), Some(default)) if value.is_empty() => Ok(default.to_string()),
+        (Ok(value), _) => Ok(value),
+        (Err(_), Some(default)) => Ok(default.to_string()),
+        (Err(_), None) => Err(anyhow!("environment variable {name} is not set")),
+    }
+}
+
+/// Return the name of the current git branch
+fn git_branch() -> Result<String, Error> {
+    let output = Command::new("git")
+        .args(["rev-parse", "--abbrev-ref", "HEAD"])
+        .output()
+        .context("failed to run git")?;
+
+    if !output.status.success() {
+        return Err(anyhow!(
+            "failed to determine the current git branch: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn interpolate_replaces_placeholders_in_nested_strings() {
+        std::env::set_var("GITHUB_DEV_APP_TEST_TUNNEL", "https://smee.io/otters");
+        let mut manifest = json!({
+            "name": "octoapp-{{port}}",
+            "hook_attributes": { "url": "${GITHUB_DEV_APP_TEST_TUNNEL}" },
+            "callback_urls": ["http://localhost:{{ port }}/callback"],
+            "public": false
+        });
+
+        interpolate(&mut manifest, &Variables::builder().port(3000).build()).unwrap();
+
+        assert_eq!(
+            json!({
+                "name": "octoapp-3000",
+                "hook_attributes": { "url": "https://smee.io/otters" },
+                "callback_urls": ["http://localhost:3000/callback"],
+                "public": false
+            }),
+            manifest
+        );
+    }
+
+    #[test]
+    fn interpolate_string_uses_default_for_unset_variable() {
+        let interpolated = interpolate_string(
+            "https://${GITHUB_DEV_APP_TEST_UNSET:-example.com}/",
+            &Variables::default(),
+        )
+        .unwrap();
+
+        assert_eq!("https://example.com/", interpolated);
+    }
+
+    #[test]
+    fn interpolate_string_keeps_other_characters() {
+        let interpolated =
+            interpolate_string("$5 {not a placeholder} }}", &Variables::default()).unwrap();
+
+        assert_eq!("$5 {not a placeholder} }}", interpolated);
+    }
+
+    #[test]
+    fn interpolate_string_unescapes_literal_placeholders() {
+        let interpolated = interpolate_string("echo $${HOME}", &Variables::default()).unwrap();
+
+        assert_eq!("echo ${HOME}", interpolated);
+    }
+
+    #[test]
+    fn interpolate_string_unescapes_literal_braces() {
+        let interpolated = interpolate_string(
+            "{{{{port}} is {{port}}",
+            &Variables::builder().port(3000).build(),
+        )
+        .unwrap();
+
+        assert_eq!("{{port}} is 3000", interpolated);
+    }
+
+    #[test]
+    fn escape_placeholders_round_trips() {
+        for string in [
+            "Hello {{ name }} from ${HOME}",
+            "${{ matrix.os }}",
+            "{{{ triple }}}",
+            "$${ escaped } and {{{{ escaped }}",
+            "$5 {not a placeholder}",
+        ] {
+            let escaped = escape_placeholders(string);
+
+            assert!(!has_placeholders(&escaped), "{escaped}");
+            assert_eq!(
+                string,
+                interpolate_string(&escaped, &Variables::default()).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn has_placeholders_finds_variables_and_builtins() {
+        assert!(has_placeholders("http://localhost:{{port}}"));
+        assert!(has_placeholders("https://${HOST:-example.com}"));
+    }
+
+    #[test]
+    fn has_placeholders_ignores_escaped_placeholders() {
+        assert!(!has_placeholders("https://example.com/$${path}"));
+        assert!(!has_placeholders("$5 {not a placeholder}"));
+        assert!(!has_placeholders("{{{{ name }}"));
+    }
+
+    #[test]
+    fn interpolate_errors_on_unset_variable() {
+        let mut manifest = json!({ "name": "${GITHUB_DEV_APP_TEST_UNSET}" });
+
+        let error = interpolate(&mut manifest, &Variables::default()).unwrap_err();
+
+        assert_eq!(
+            "failed to interpolate /name: environment variable GITHUB_DEV_APP_TEST_UNSET is not set",
+            format!("{error:#}")
+        );
+    }
+
+    #[test]
+    fn interpolate_string_errors_on_unknown_placeholder() {
+        let error = interpolate_string("{{branch}}", &Variables::default()).unwrap_err();
+
+        assert_eq!("unknown placeholder {{branch}}", error.to_string());
+    }
+
+    #[test]
+    fn interpolate_string_errors_on_missing_port() {
+        let error = interpolate_string("{{port}}", &Variables::default()).unwrap_err();
+
+        assert_eq!(
+            "{{port}} is only available when registering an app",
+            error.to_string()
+        );
+    }
+
+    #[test]
+    fn interpolate_string_errors_on_unterminated_placeholder() {
+        let error = interpolate_string("${HOME", &Variables::default()).unwrap_err();
+
+        assert_eq!("unterminated placeholder in `${HOME`", error.to_string());
+    }
+
+    #[test]
+    fn trait_send() {
+        fn assert_send<T: Send>() {}
+        assert_send::<Variables>();
+    }
+
+    #[test]
+    fn trait_sync() {
+        fn assert_sync<T: Sync>() {}
+        assert_sync::<Variables>();
+    }
+
+    #[test]
+    fn trait_unpin() {
+        fn assert_unpin<T: Unpin>() {}
+        assert_unpin::<Variables>();
+    }
+}
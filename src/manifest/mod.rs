@@ -0,0 +1,614 @@
+//! Manifest for a GitHub App
+
+pub use self::args::*;
+pub use self::cargo::*;
+pub use self::catalog::{AccessLevel, Event, Permission};
+pub use self::command::*;
+pub use self::format::*;
+pub use self::interpolation::*;
+pub use self::overlay::*;
+pub use self::overrides::*;
+pub use self::schema::*;
+
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use std::hash::{Hash, Hasher};
+use std::ops::Deref;
+use std::path::Path;
+
+use anyhow::{anyhow, Context, Error};
+use getset::{Getters, Setters};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use typed_builder::TypedBuilder;
+use typed_fields::name;
+
+use crate::error::CliError;
+
+mod args;
+mod cargo;
+mod catalog;
+mod check;
+mod command;
+mod format;
+mod interpolation;
+mod overlay;
+mod overrides;
+mod position;
+mod schema;
+
+name!(Name);
+name!(Description);
+name!(HomepageUrl);
+name!(WebhookUrl);
+name!(RedirectUrl);
+name!(CallbackUrl);
+name!(SetupUrl);
+
+name!(SerializedManifest);
+
+/// Manifest for a GitHub App
+///
+/// GitHub Apps can be created through GitHub's API by providing a manifest for the app. The
+/// manifest contains the configuration for the app, including its name, permissions, and callback
+/// URLs.
+///
+/// The manifest has to be provided by the user. To make customization easier, some fields in the
+/// manifest can be overwritten using command-line arguments when registering the app.
+#[derive(
+    Clone, Eq, PartialEq, Debug, Getters, Setters, Deserialize, Serialize, JsonSchema, TypedBuilder,
+)]
+pub struct Manifest {
+    /// The name of the GitHub App
+    #[builder(default, setter(strip_option))]
+    #[getset(get = "pub", set = "pub")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schemars(with = "Option<String>")]
+    name: Option<Name>,
+
+    /// The homepage of your GitHub App
+    #[getset(get = "pub", set = "pub")]
+    #[schemars(with = "String", url)]
+    url: HomepageUrl,
+
+    /// The configuration of the GitHub App's webhook
+    #[builder(default, setter(strip_option))]
+    #[getset(get = "pub", set = "pub")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    hook_attributes: Option<HookAttributes>,
+
+    /// The full URL to redirect to after a user initiates the registration of a GitHub App from a
+    /// manifest
+    #[builder(default, setter(strip_option))]
+    #[getset(get = "pub", set = "pub")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schemars(with = "Option<String>", url)]
+    redirect_url: Option<RedirectUrl>,
+
+    /// A full URL to redirect to after someone authorizes an installation
+    ///
+    /// Up to 10 callback URLs can be provided.
+    #[builder(default, setter(strip_option))]
+    #[getset(get = "pub")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schemars(with = "Option<Vec<String>>", length(max = 10))]
+    callback_urls: Option<Vec<CallbackUrl>>,
+
+    /// A full URL to redirect users to after they install your GitHub App if additional setup is
+    /// required
+    #[builder(default, setter(strip_option))]
+    #[getset(get = "pub")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schemars(with = "Option<String>", url)]
+    setup_url: Option<SetupUrl>,
+
+    /// A description of the GitHub App
+    #[builder(default, setter(strip_option))]
+    #[getset(get = "pub", set = "pub")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schemars(with = "Option<String>")]
+    description: Option<Description>,
+
+    /// Set to `true` when your GitHub App is available to the public or `false` when it is only
+    /// accessible to the owner of the app
+    #[builder(default, setter(strip_option))]
+    #[getset(get = "pub", set = "pub")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    public: Option<bool>,
+
+    /// The list of events the GitHub App subscribes to
+    #[builder(default, setter(strip_option))]
+    #[getset(get = "pub", set = "pub")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    default_events: Option<Vec<Event>>,
+
+    /// The set of permissions needed by the GitHub App
+    ///
+    /// The format of the object uses the permission name for the key (for example, issues) and the
+    /// access type for the value (for example, write). The permissions are serialized in the order
+    /// of their names, so that the serialized manifest is stable.
+    #[builder(default, setter(strip_option))]
+    #[getset(get = "pub", set = "pub")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schemars(schema_with = "permissions_schema", default)]
+    default_permissions: Option<BTreeMap<Permission, AccessLevel>>,
+
+    /// Set to `true` to request the user to authorize the GitHub App, after the GitHub App is
+    /// installed
+    #[builder(default, setter(strip_option))]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[getset(get = "pub")]
+    request_oauth_on_install: Option<bool>,
+
+    /// Set to `true` to redirect users to the setup_url after they update the GitHub installation
+    #[builder(default, setter(strip_option))]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[getset(get = "pub")]
+    setup_on_update: Option<bool>,
+
+    /// The JSON Schema with which editors validate the manifest file
+    ///
+    /// The schema is only used by editors, and is not sent to GitHub.
+    #[builder(default, setter(strip_option))]
+    #[getset(get = "pub")]
+    #[serde(rename = "$schema", default, skip_serializing)]
+    schema: Option<String>,
+
+    /// Fields that are not known to this tool
+    ///
+    /// GitHub might add new fields to the manifest format. These fields are kept and sent to GitHub
+    /// unchanged, so that they can be used before this tool knows about them.
+    #[builder(default)]
+    #[getset(get = "pub")]
+    #[serde(flatten)]
+    #[schemars(skip)]
+    unknown_fields: UnknownFields,
+}
+
+/// Configuration of the GitHub App's webhook
+///
+/// The webhook configuration specifies the URL of the server that will receive the webhook `POST`
+/// requests and whether the webhook is active.
+#[derive(
+    Clone,
+    Eq,
+    PartialEq,
+    Ord,
+    PartialOrd,
+    Hash,
+    Debug,
+    Getters,
+    Setters,
+    Deserialize,
+    Serialize,
+    JsonSchema,
+    TypedBuilder,
+)]
+pub struct HookAttributes {
+    /// The URL of the server that will receive the webhook `POST` requests
+    #[builder(setter(into))]
+    #[getset(get = "pub", set = "pub")]
+    #[schemars(with = "String", url)]
+    url: WebhookUrl,
+
+    /// Deliver event details when this hook is triggered, defaults to true
+    #[builder(default, setter(strip_option))]
+    #[getset(get = "pub", set = "pub")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    active: Option<bool>,
+
+    /// Fields that are not known to this tool
+    #[builder(default)]
+    #[getset(get = "pub")]
+    #[serde(flatten)]
+    #[schemars(skip)]
+    unknown_fields: UnknownFields,
+}
+
+/// Fields of a manifest that are not known to this tool, with their values
+///
+/// JSON values can neither be ordered nor hashed, so the fields are compared by their serialized
+/// form. This keeps the derived traits of the types that contain them.
+#[derive(Clone, Eq, PartialEq, Debug, Default, Deserialize, Serialize)]
+#[serde(transparent)]
+pub struct UnknownFields(BTreeMap<String, Value>);
+
+impl UnknownFields {
+    /// Serialize the fields to compare and hash them
+    fn canonical(&self) -> String {
+        serde_json::to_string(&self.0).unwrap_or_default()
+    }
+}
+
+impl Deref for UnknownFields {
+    type Target = BTreeMap<String, Value>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl Ord for UnknownFields {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.canonical().cmp(&other.canonical())
+    }
+}
+
+impl PartialOrd for UnknownFields {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Hash for UnknownFields {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.canonical().hash(state);
+    }
+}
+
+impl Manifest {
+    /// Load the manifest for a command
+    ///
+    /// If a path is given, the manifest is read from the file. Otherwise, the manifest is read from
+    /// the metadata of the Cargo package or workspace in the current directory. Profiles are only
+    /// supported for manifest files.
+    pub fn load(
+        path: Option<&Path>,
+        profile: Option<&str>,
+        variables: &Variables,
+    ) -> Result<Self, Error> {
+        if let Some(path) = path {
+            return Self::from_file_with_variables(path, profile, variables);
+        }
+
+        if let Some(profile) = profile {
+            return Err(anyhow!(
+                "profile {profile} requires a manifest file, but none was given"
+            ));
+        }
+
+        let found = find_in_current_directory()?;
+
+        Self::from_value(found.manifest().clone(), variables).with_context(|| {
+            format!(
+                "failed to load manifest from [{}] in {}",
+                found.table(),
+                found.path().display()
+            )
+        })
+    }
+
+    /// Initialize a manifest from a file, with the values of the built-in placeholders
+    ///
+    /// The manifests that the file extends and the overlay of the profile are merged into the
+    /// manifest before its placeholders are replaced.
+    pub fn from_file_with_variables(
+        path: &Path,
+        profile: Option<&str>,
+        variables: &Variables,
+    ) -> Result<Self, Error> {
+        Self::from_value(read_manifest(path, profile)?, variables)
+    }
+
+    /// Initialize a manifest from a JSON value
+    ///
+    /// The placeholders in the strings of the value are replaced before the value is deserialized
+    /// into a manifest.
+    pub fn from_value(mut value: Value, variables: &Variables) -> Result<Self, Error> {
+        interpolate(&mut value, variables)?;
+
+        serde_json::from_value(value)
+            .with_context(|| CliError::InvalidManifest("failed to deserialize manifest".into()))
+    }
+
+    /// Return the names of the fields that are not known to this tool
+    ///
+    /// Fields in nested objects are returned with the name of their parent, e.g.
+    /// `hook_attributes.secret`.
+    pub fn unknown_field_names(&self) -> Vec<String> {
+        let hook_fields = self
+            .hook_attributes
+            .iter()
+            .flat_map(|hook_attributes| hook_attributes.unknown_fields.keys())
+            .map(|field| format!("hook_attributes.{field}"));
+
+        self.unknown_fields
+            .keys()
+            .cloned()
+            .chain(hook_fields)
+            .collect()
+    }
+}
+
+impl TryFrom<Manifest> for SerializedManifest {
+    type Error = Error;
+
+    fn try_from(manifest: Manifest) -> Result<Self, Self::Error> {
+        serde_json::to_string(&manifest)
+            .context("failed to serialize manifest")
+            .map(Into::into)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use indoc::indoc;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+    use tempfile::NamedTempFile;
+
+    use super::*;
+
+    const JSON: &str = indoc! {r#"
+            {
+               "name": "Octoapp",
+               "url": "https://www.example.com",
+               "hook_attributes": {
+                 "url": "https://example.com/github/events"
+               },
+               "redirect_url": "https://example.com/redirect",
+               "callback_urls": [
+                 "https://example.com/callback"
+               ],
+               "public": true,
+               "default_permissions": {
+                 "issues": "write",
+                 "checks": "write"
+               },
+               "default_events": [
+                 "issues",
+                 "issue_comment",
+                 "check_suite",
+                 "check_run"
+               ]
+            }
+        "#};
+
+    #[test]
+    fn from_file() {
+        let mut file = NamedTempFile::new().unwrap();
+        file.write_all(JSON.as_bytes()).unwrap();
+
+        let manifest =
+            Manifest::from_file_with_variables(file.path(), None, &Variables::default()).unwrap();
+
+        assert_eq!(&Some("Octoapp".into()), manifest.name());
+    }
+
+    #[test]
+    fn from_file_detects_yaml() {
+        let mut file = tempfile::Builder::new().suffix(".yml").tempfile().unwrap();
+        file.write_all(b"name: Octoapp\nurl: https://www.example.com\n")
+            .unwrap();
+
+        let manifest =
+            Manifest::from_file_with_variables(file.path(), None, &Variables::default()).unwrap();
+
+        assert_eq!(&Some("Octoapp".into()), manifest.name());
+    }
+
+    #[test]
+    fn from_file_detects_toml() {
+        let mut file = tempfile::Builder::new().suffix(".toml").tempfile().unwrap();
+        file.write_all(b"name = \"Octoapp\"\nurl = \"https://www.example.com\"\n")
+            .unwrap();
+
+        let manifest =
+            Manifest::from_file_with_variables(file.path(), None, &Variables::default()).unwrap();
+
+        assert_eq!(&Some("Octoapp".into()), manifest.name());
+    }
+
+    #[test]
+    fn from_file_applies_profile() {
+        let directory = tempfile::TempDir::new().unwrap();
+        let path = directory.path().join("manifest.json");
+        std::fs::write(&path, JSON).unwrap();
+        std::fs::write(
+            directory.path().join("manifest.dev.json"),
+            r#"{"name": "Octoapp (dev)"}"#,
+        )
+        .unwrap();
+
+        let manifest =
+            Manifest::from_file_with_variables(&path, Some("dev"), &Variables::default()).unwrap();
+
+        assert_eq!(&Some("Octoapp (dev)".into()), manifest.name());
+    }
+
+    #[test]
+    fn from_file_errors_on_empty_file() {
+        let file = NamedTempFile::new().unwrap();
+
+        let error = Manifest::from_file_with_variables(file.path(), None, &Variables::default())
+            .unwrap_err();
+
+        assert!(format!("{:?}", error).contains("EOF while parsing a value"));
+    }
+
+    #[test]
+    fn from_file_errors_on_missing_file() {
+        let error = Manifest::from_file_with_variables(
+            Path::new("missing.json"),
+            None,
+            &Variables::default(),
+        )
+        .unwrap_err();
+
+        assert!(format!("{:?}", error).contains("No such file or directory"));
+    }
+
+    #[test]
+    fn from_value_errors_on_missing_url() {
+        let json = Format::Json.parse(r#"{"name": "Octoapp"}"#).unwrap();
+
+        let error = Manifest::from_value(json, &Variables::default()).unwrap_err();
+
+        assert!(format!("{:?}", error).contains("missing field `url`"));
+    }
+
+    #[test]
+    fn from_value() {
+        let manifest =
+            Manifest::from_value(serde_json::from_str(JSON).unwrap(), &Variables::default())
+                .unwrap();
+
+        assert_eq!(&Some("Octoapp".into()), manifest.name());
+    }
+
+    #[test]
+    fn from_value_errors_on_missing_field() {
+        let json = r#"{"name": "Octoapp"}"#;
+
+        let error =
+            Manifest::from_value(serde_json::from_str(json).unwrap(), &Variables::default())
+                .unwrap_err();
+
+        assert!(format!("{:?}", error).contains("missing field `url`"));
+    }
+
+    #[test]
+    fn from_value_errors_on_unknown_permission() {
+        let json = r#"{"url": "https://example.com", "default_permissions": {"isues": "write"}}"#;
+
+        let error =
+            Manifest::from_value(serde_json::from_str(json).unwrap(), &Variables::default())
+                .unwrap_err();
+
+        assert!(format!("{:?}", error).contains("unknown variant `isues`"));
+    }
+
+    #[test]
+    fn from_value_errors_on_unknown_access_level() {
+        let json = r#"{"url": "https://example.com", "default_permissions": {"issues": "wirte"}}"#;
+
+        let error =
+            Manifest::from_value(serde_json::from_str(json).unwrap(), &Variables::default())
+                .unwrap_err();
+
+        assert!(format!("{:?}", error).contains("unknown variant `wirte`"));
+    }
+
+    #[test]
+    fn from_value_interpolates_placeholders() {
+        let json = r#"{"name": "octoapp-{{port}}", "url": "http://localhost:{{port}}"}"#;
+
+        let manifest = Manifest::from_value(
+            serde_json::from_str(json).unwrap(),
+            &Variables::builder().port(3000).build(),
+        )
+        .unwrap();
+
+        assert_eq!(&Some("octoapp-3000".into()), manifest.name());
+        assert_eq!("http://localhost:3000", manifest.url().get());
+    }
+
+    #[test]
+    fn from_value_keeps_unknown_events() {
+        let json = r#"{"url": "https://example.com", "default_events": ["issues", "new_event"]}"#;
+
+        let manifest =
+            Manifest::from_value(serde_json::from_str(json).unwrap(), &Variables::default())
+                .unwrap();
+
+        assert_eq!(
+            &Some(vec![Event::Issues, Event::Other("new_event".into())]),
+            manifest.default_events()
+        );
+    }
+
+    #[test]
+    fn from_value_keeps_unknown_fields() {
+        let json = json!({
+            "url": "https://example.com",
+            "hook_attributes": { "url": "https://example.com/events", "secret": "otters" },
+            "future_field": { "nested": [1, 2] }
+        });
+
+        let manifest = Manifest::from_value(json.clone(), &Variables::default()).unwrap();
+
+        assert_eq!(
+            vec!["future_field", "hook_attributes.secret"],
+            manifest.unknown_field_names()
+        );
+        assert_eq!(json, serde_json::to_value(&manifest).unwrap());
+    }
+
+    #[test]
+    fn from_value_drops_schema() {
+        let json = json!({
+            "$schema": "https://example.com/manifest.schema.json",
+            "url": "https://example.com"
+        });
+
+        let manifest = Manifest::from_value(json, &Variables::default()).unwrap();
+
+        assert!(manifest.unknown_field_names().is_empty());
+        assert_eq!(
+            json!({ "url": "https://example.com" }),
+            serde_json::to_value(&manifest).unwrap()
+        );
+    }
+
+    #[test]
+    fn unknown_fields_are_ordered_by_their_values() {
+        let hook_attributes = |secret: &str| -> HookAttributes {
+            serde_json::from_value(json!({ "url": "https://example.com", "secret": secret }))
+                .unwrap()
+        };
+
+        assert!(hook_attributes("a") < hook_attributes("b"));
+    }
+
+    #[test]
+    fn trait_deserialize() {
+        let manifest: Manifest = serde_json::from_str(JSON).unwrap();
+
+        assert_eq!(&Some("Octoapp".into()), manifest.name());
+    }
+
+    #[test]
+    fn trait_send() {
+        fn assert_send<T: Send>() {}
+        assert_send::<Manifest>();
+    }
+
+    #[test]
+    fn trait_serialize() {
+        let manifest = Manifest::builder()
+            .url("https://www.example.com".into())
+            .build();
+
+        let expected = r#"{"url":"https://www.example.com"}"#;
+
+        let json = serde_json::to_string(&manifest).unwrap();
+
+        assert_eq!(expected, json);
+    }
+
+    #[test]
+    fn trait_serialize_orders_permissions_by_name() {
+        let manifest =
+            Manifest::from_value(serde_json::from_str(JSON).unwrap(), &Variables::default())
+                .unwrap();
+
+        let json = serde_json::to_string(&manifest).unwrap();
+
+        assert!(json.contains(r#""default_permissions":{"checks":"write","issues":"write"}"#));
+    }
+
+    #[test]
+    fn trait_sync() {
+        fn assert_sync<T: Sync>() {}
+        assert_sync::<Manifest>();
+    }
+
+    #[test]
+    fn trait_unpin() {
+        fn assert_unpin<T: Unpin>() {}
+        assert_unpin::<Manifest>();
+    }
+}
@@ -0,0 +1,224 @@
+//! Overlays that customize a base manifest
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Context, Error};
+use serde_json::Value;
+
+use crate::error::CliError;
+
+use super::Format;
+
+/// The key with which a manifest extends another manifest
+const EXTENDS_KEY: &str = "extends";
+
+/// Read a manifest file and apply its base manifests and profile
+///
+/// A manifest can extend another manifest by setting `extends` to the path of the other manifest,
+/// relative to its own file. If a profile is given, the overlay next to the manifest is applied on
+/// top, e.g. `manifest.staging.json` for the profile `staging` of `manifest.json`.
+pub fn read_manifest(path: &Path, profile: Option<&str>) -> Result<Value, Error> {
+    let mut manifest = read_with_extends(path, &mut Vec::new())?;
+
+    if let Some(profile) = profile {
+        let overlay_path = profile_path(path, profile);
+        if !overlay_path.is_file() {
+            return Err(anyhow!(
+                "profile {profile} not found, expected the overlay {}",
+                overlay_path.display()
+            ));
+        }
+
+        merge(
+            &mut manifest,
+            read_with_extends(&overlay_path, &mut Vec::new())?,
+        );
+    }
+
+    Ok(manifest)
+}
+
+/// Check whether a manifest extends another manifest
+pub fn has_base_manifest(manifest: &Value) -> bool {
+    manifest.get(EXTENDS_KEY).is_some()
+}
+
+/// Merge an overlay into a manifest
+///
+/// Objects are merged recursively, while all other values in the overlay replace the values in the
+/// manifest. A `null` in the overlay removes the value from the manifest, e.g. to drop a permission.
+pub fn merge(manifest: &mut Value, overlay: Value) {
+    match (manifest, overlay) {
+        (Value::Object(manifest), Value::Object(overlay)) => {
+            for (key, value) in overlay {
+                match (manifest.get_mut(&key), value) {
+                    (_, Value::Null) => {
+                        manifest.remove(&key);
+                    }
+                    (Some(existing), value) => merge(existing, value),
+                    (None, value) => {
+                        manifest.insert(key, value);
+                    }
+                }
+            }
+        }
+        (manifest, overlay) => *manifest = overlay,
+    }
+}
+
+/// Read a manifest file and merge it into the manifests that it extends
+fn read_with_extends(path: &Path, chain: &mut Vec<PathBuf>) -> Result<Value, Error> {
+    let source = std::fs::read_to_string(path).context(CliError::ManifestUnreadable)?;
+
+    let canonical = path.canonicalize().context(CliError::ManifestUnreadable)?;
+    if chain.contains(&canonical) {
+        return Err(anyhow!(
+            "manifest {} extends itself through {}",
+            path.display(),
+            chain[0].display()
+        ));
+    }
+    chain.push(canonical);
+
+    let mut manifest = Format::from_path(path).parse(&source)?;
+
+    let extends = match manifest.as_object_mut() {
+        Some(object) => object.remove(EXTENDS_KEY),
+        None => None,
+    };
+
+    match extends {
+        None => Ok(manifest),
+        Some(Value::String(base)) => {
+            let base_path = path.parent().unwrap_or(Path::new("")).join(base);
+            let mut base = read_with_extends(&base_path, chain)?;
+
+            merge(&mut base, manifest);
+            Ok(base)
+        }
+        Some(_) => Err(anyhow!(
+            "`{EXTENDS_KEY}` in {} must be the path to a manifest",
+            path.display()
+        )),
+    }
+}
+
+/// Return the path of the overlay for a profile
+pub fn profile_path(path: &Path, profile: &str) -> PathBuf {
+    let stem = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy())
+        .unwrap_or_default();
+
+    let file_name = match path.extension() {
+        Some(extension) => format!("{stem}.{profile}.{}", extension.to_string_lossy()),
+        None => format!("{stem}.{profile}"),
+    };
+
+    path.with_file_name(file_name)
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    use super::*;
+
+    #[test]
+    fn read_manifest_applies_profile() {
+        let directory = TempDir::new().unwrap();
+        let path = directory.path().join("manifest.json");
+        std::fs::write(
+            &path,
+            r#"{"name": "octoapp", "url": "https://example.com", "public": true}"#,
+        )
+        .unwrap();
+        std::fs::write(
+            directory.path().join("manifest.staging.json"),
+            r#"{"name": "octoapp-staging", "public": null}"#,
+        )
+        .unwrap();
+
+        let manifest = read_manifest(&path, Some("staging")).unwrap();
+
+        assert_eq!(
+            json!({"name": "octoapp-staging", "url": "https://example.com"}),
+            manifest
+        );
+    }
+
+    #[test]
+    fn read_manifest_errors_on_missing_profile() {
+        let directory = TempDir::new().unwrap();
+        let path = directory.path().join("manifest.json");
+        std::fs::write(&path, r#"{"url": "https://example.com"}"#).unwrap();
+
+        let error = read_manifest(&path, Some("preview")).unwrap_err();
+
+        assert!(error
+            .to_string()
+            .starts_with("profile preview not found, expected the overlay"));
+    }
+
+    #[test]
+    fn read_manifest_resolves_extends_relative_to_file() {
+        let directory = TempDir::new().unwrap();
+        std::fs::create_dir(directory.path().join("apps")).unwrap();
+        std::fs::write(
+            directory.path().join("base.yml"),
+            "url: https://example.com\ndefault_permissions:\n  issues: write\n",
+        )
+        .unwrap();
+        let path = directory.path().join("apps/preview.json");
+        std::fs::write(
+            &path,
+            r#"{"extends": "../base.yml", "default_permissions": {"checks": "read"}}"#,
+        )
+        .unwrap();
+
+        let manifest = read_manifest(&path, None).unwrap();
+
+        assert_eq!(
+            json!({
+                "url": "https://example.com",
+                "default_permissions": {"issues": "write", "checks": "read"}
+            }),
+            manifest
+        );
+    }
+
+    #[test]
+    fn read_manifest_errors_on_cycle() {
+        let directory = TempDir::new().unwrap();
+        let path = directory.path().join("a.json");
+        std::fs::write(&path, r#"{"extends": "b.json"}"#).unwrap();
+        std::fs::write(directory.path().join("b.json"), r#"{"extends": "a.json"}"#).unwrap();
+
+        let error = read_manifest(&path, None).unwrap_err();
+
+        assert!(error.to_string().contains("extends itself"));
+    }
+
+    #[test]
+    fn merge_replaces_arrays() {
+        let mut manifest = json!({"default_events": ["issues", "push"]});
+
+        merge(&mut manifest, json!({"default_events": ["check_run"]}));
+
+        assert_eq!(json!({"default_events": ["check_run"]}), manifest);
+    }
+
+    #[test]
+    fn profile_path_inserts_profile_before_extension() {
+        assert_eq!(
+            PathBuf::from("apps/manifest.staging.json"),
+            profile_path(Path::new("apps/manifest.json"), "staging")
+        );
+        assert_eq!(
+            PathBuf::from("manifest.dev"),
+            profile_path(Path::new("manifest"), "dev")
+        );
+    }
+}
@@ -0,0 +1,322 @@
+//! Command-line overrides for the fields of a manifest
+
+use clap::Args;
+use getset::{CopyGetters, Getters};
+use typed_builder::TypedBuilder;
+use url::Url;
+
+use super::{AccessLevel, Event, HookAttributes, Manifest, Permission};
+
+/// Command-line arguments that override the fields of a manifest
+///
+/// The overrides are applied on top of the manifest file, which allows registering variants of an
+/// app without editing the shared manifest.
+#[derive(
+    Clone,
+    Eq,
+    PartialEq,
+    Ord,
+    PartialOrd,
+    Hash,
+    Debug,
+    Default,
+    Args,
+    CopyGetters,
+    Getters,
+    TypedBuilder,
+)]
+pub struct ManifestOverrides {
+    /// Override the name of the app
+    #[arg(long)]
+    #[builder(default, setter(into, strip_option))]
+    #[getset(get = "pub")]
+    name: Option<String>,
+
+    /// Override the description of the app
+    #[arg(long)]
+    #[builder(default, setter(into, strip_option))]
+    #[getset(get = "pub")]
+    description: Option<String>,
+
+    /// Override the homepage of the app
+    #[arg(long)]
+    #[builder(default, setter(strip_option))]
+    #[getset(get = "pub")]
+    url: Option<Url>,
+
+    /// Override the URL to which the app's webhooks are delivered
+    #[arg(long)]
+    #[builder(default, setter(strip_option))]
+    #[getset(get = "pub")]
+    webhook_url: Option<Url>,
+
+    /// Deactivate the app's webhook, if the manifest or `--webhook-url` configures one
+    #[arg(long)]
+    #[builder(default)]
+    #[getset(get_copy = "pub")]
+    webhook_inactive: bool,
+
+    /// Make the app available to the public instead of only its owner
+    #[arg(long)]
+    #[builder(default)]
+    #[getset(get_copy = "pub")]
+    public: bool,
+
+    /// Subscribe the app to an additional webhook event
+    #[arg(long = "event", value_name = "EVENT", value_parser = parse_event)]
+    #[builder(default)]
+    #[getset(get = "pub")]
+    events: Vec<Event>,
+
+    /// Request an additional permission, e.g. `--permission issues=write`
+    #[arg(long = "permission", value_name = "NAME=LEVEL", value_parser = parse_permission)]
+    #[builder(default)]
+    #[getset(get = "pub")]
+    permissions: Vec<(Permission, AccessLevel)>,
+}
+
+impl ManifestOverrides {
+    /// Apply the overrides to a manifest
+    ///
+    /// Events are added to the events in the manifest, and permissions replace the access level of
+    /// the same permission in the manifest.
+    pub fn apply(&self, manifest: &mut Manifest) {
+        if let Some(name) = &self.name {
+            manifest.set_name(Some(name.as_str().into()));
+        }
+
+        if let Some(description) = &self.description {
+            manifest.set_description(Some(description.as_str().into()));
+        }
+
+        if let Some(url) = &self.url {
+            manifest.set_url(url.as_str().into());
+        }
+
+        let webhook_url = match (&self.webhook_url, manifest.hook_attributes()) {
+            (Some(url), _) => Some(url.to_string()),
+            (None, Some(hook_attributes)) if self.webhook_inactive => {
+                Some(hook_attributes.url().get().to_string())
+            }
+            // An app without a webhook has nothing to deactivate
+            _ => None,
+        };
+
+        // The existing webhook is changed in place, so that its other fields are kept
+        if let Some(url) = webhook_url {
+            let mut hook_attributes = match manifest.hook_attributes().clone() {
+                Some(hook_attributes) => hook_attributes,
+                None => HookAttributes::builder().url(url.as_str()).build(),
+            };
+            hook_attributes.set_url(url.into());
+            if self.webhook_inactive {
+                hook_attributes.set_active(Some(false));
+            }
+
+            manifest.set_hook_attributes(Some(hook_attributes));
+        }
+
+        if self.public {
+            manifest.set_public(Some(true));
+        }
+
+        if !self.events.is_empty() {
+            let mut events = manifest.default_events().clone().unwrap_or_default();
+            for event in &self.events {
+                if !events.contains(event) {
+                    events.push(event.clone());
+                }
+            }
+            manifest.set_default_events(Some(events));
+        }
+
+        if !self.permissions.is_empty() {
+            let mut permissions = manifest.default_permissions().clone().unwrap_or_default();
+            permissions.extend(self.permissions.iter().copied());
+            manifest.set_default_permissions(Some(permissions));
+        }
+    }
+}
+
+/// Parse a webhook event
+fn parse_event(event: &str) -> Result<Event, String> {
+    Ok(Event::from(event))
+}
+
+/// Parse a permission in the format `name=level`
+fn parse_permission(permission: &str) -> Result<(Permission, AccessLevel), String> {
+    let (name, level) = permission
+        .split_once('=')
+        .ok_or_else(|| format!("invalid permission `{permission}`, expected name=level"))?;
+
+    let name: Permission = name.parse().map_err(|error| format!("{error}"))?;
+    let level: AccessLevel = level.parse().map_err(|error| format!("{error}"))?;
+
+    if !name.access_levels().contains(&level) {
+        return Err(format!(
+            "access level {level} is not available for permission `{name}`"
+        ));
+    }
+
+    Ok((name, level))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn manifest() -> Manifest {
+        let mut manifest = Manifest::builder()
+            .url("https://example.com".into())
+            .default_events(vec![Event::Issues])
+            .default_permissions(BTreeMap::from([(Permission::Issues, AccessLevel::Read)]))
+            .build();
+        manifest.set_name(Some("octoapp".into()));
+
+        manifest
+    }
+
+    #[test]
+    fn apply_overrides_fields() {
+        let overrides = ManifestOverrides::builder()
+            .name("octoapp-dev")
+            .description("Development version")
+            .url(Url::parse("https://dev.example.com").unwrap())
+            .public(true)
+            .build();
+        let mut manifest = manifest();
+
+        overrides.apply(&mut manifest);
+
+        assert_eq!(&Some("octoapp-dev".into()), manifest.name());
+        assert_eq!(&Some("Development version".into()), manifest.description());
+        assert_eq!("https://dev.example.com/", manifest.url().get());
+        assert_eq!(&Some(true), manifest.public());
+    }
+
+    #[test]
+    fn apply_adds_events_and_permissions() {
+        let overrides = ManifestOverrides::builder()
+            .events(vec![Event::Issues, Event::Push])
+            .permissions(vec![
+                (Permission::Issues, AccessLevel::Write),
+                (Permission::Contents, AccessLevel::Read),
+            ])
+            .build();
+        let mut manifest = manifest();
+
+        overrides.apply(&mut manifest);
+
+        assert_eq!(
+            &Some(vec![Event::Issues, Event::Push]),
+            manifest.default_events()
+        );
+        assert_eq!(
+            &Some(BTreeMap::from([
+                (Permission::Contents, AccessLevel::Read),
+                (Permission::Issues, AccessLevel::Write),
+            ])),
+            manifest.default_permissions()
+        );
+    }
+
+    #[test]
+    fn apply_deactivates_existing_webhook() {
+        let overrides = ManifestOverrides::builder().webhook_inactive(true).build();
+        let mut manifest = manifest();
+        manifest.set_hook_attributes(Some(
+            HookAttributes::builder()
+                .url("https://smee.io/otters")
+                .build(),
+        ));
+
+        overrides.apply(&mut manifest);
+
+        let hook_attributes = manifest.hook_attributes().as_ref().unwrap();
+        assert_eq!("https://smee.io/otters", hook_attributes.url().get());
+        assert_eq!(&Some(false), hook_attributes.active());
+    }
+
+    #[test]
+    fn apply_keeps_unknown_fields_of_webhook() {
+        let overrides = ManifestOverrides::builder()
+            .webhook_url(Url::parse("https://smee.io/otters").unwrap())
+            .build();
+        let mut manifest: Manifest = serde_json::from_value(serde_json::json!({
+            "url": "https://example.com",
+            "hook_attributes": { "url": "https://example.com/events", "secret": "otters" }
+        }))
+        .unwrap();
+
+        overrides.apply(&mut manifest);
+
+        let hook_attributes = manifest.hook_attributes().as_ref().unwrap();
+        assert_eq!("https://smee.io/otters", hook_attributes.url().get());
+        assert_eq!(
+            Some(&serde_json::json!("otters")),
+            hook_attributes.unknown_fields().get("secret")
+        );
+    }
+
+    #[test]
+    fn apply_skips_deactivating_missing_webhook() {
+        let overrides = ManifestOverrides::builder().webhook_inactive(true).build();
+        let mut manifest = manifest();
+
+        overrides.apply(&mut manifest);
+
+        assert_eq!(&None, manifest.hook_attributes());
+    }
+
+    #[test]
+    fn apply_keeps_manifest_without_overrides() {
+        let mut manifest = manifest();
+
+        ManifestOverrides::default().apply(&mut manifest);
+
+        assert_eq!(self::manifest(), manifest);
+    }
+
+    #[test]
+    fn parse_permission_splits_name_and_level() {
+        assert_eq!(
+            Ok((Permission::PullRequests, AccessLevel::Write)),
+            parse_permission("pull_requests=write")
+        );
+    }
+
+    #[test]
+    fn parse_permission_errors_on_unavailable_level() {
+        assert_eq!(
+            Err("access level admin is not available for permission `issues`".to_string()),
+            parse_permission("issues=admin")
+        );
+    }
+
+    #[test]
+    fn parse_permission_errors_without_equals_sign() {
+        assert!(parse_permission("issues").is_err());
+    }
+
+    #[test]
+    fn trait_send() {
+        fn assert_send<T: Send>() {}
+        assert_send::<ManifestOverrides>();
+    }
+
+    #[test]
+    fn trait_sync() {
+        fn assert_sync<T: Sync>() {}
+        assert_sync::<ManifestOverrides>();
+    }
+
+    #[test]
+    fn trait_unpin() {
+        fn assert_unpin<T: Unpin>() {}
+        assert_unpin::<ManifestOverrides>();
+    }
+}
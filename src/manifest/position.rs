@@ -0,0 +1,318 @@
+//! Positions of the values in a JSON document
+
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+
+use getset::CopyGetters;
+use serde::Serialize;
+
+/// A position in a JSON document
+///
+/// Lines and columns start at 1, matching the positions that are reported by `serde_json` and
+/// understood by editors.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, CopyGetters, Serialize)]
+pub struct Position {
+    /// The line of the position
+    #[getset(get_copy = "pub")]
+    line: usize,
+
+    /// The column of the position
+    #[getset(get_copy = "pub")]
+    column: usize,
+}
+
+impl Position {
+    /// Create a new position
+    pub fn new(line: usize, column: usize) -> Self {
+        Self { line, column }
+    }
+}
+
+impl Display for Position {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.line, self.column)
+    }
+}
+
+/// Index of the positions of all values in a JSON document
+///
+/// `serde_json` discards the positions of values after parsing. This index walks the source of a
+/// document and records where each value and object key starts, so that problems in the document
+/// can be reported with a position. Values are identified by their JSON pointer, e.g.
+/// `/default_permissions/issues`.
+#[derive(Clone, Eq, PartialEq, Debug, Default)]
+pub struct PositionIndex {
+    /// The positions of the object keys
+    keys: HashMap<String, Position>,
+
+    /// The positions of the values
+    values: HashMap<String, Position>,
+}
+
+impl PositionIndex {
+    /// Build the index for a JSON document
+    ///
+    /// The document is expected to be valid JSON. Parsing stops at the first syntax error, and only
+    /// the values up to the error are indexed.
+    pub fn new(source: &str) -> Self {
+        let mut walker = Walker {
+            chars: source.chars().collect(),
+            index: 0,
+            line: 1,
+            column: 1,
+            positions: Self::default(),
+        };
+
+        walker.value("");
+
+        walker.positions
+    }
+
+    /// Return the position of the key of an object member
+    ///
+    /// Falls back to the position of the value for array items and the root document.
+    pub fn key(&self, pointer: &str) -> Option<Position> {
+        self.keys
+            .get(pointer)
+            .or_else(|| self.values.get(pointer))
+            .copied()
+    }
+
+    /// Return the position of a value
+    pub fn value(&self, pointer: &str) -> Option<Position> {
+        self.values.get(pointer).copied()
+    }
+}
+
+/// Escape a key so that it can be used as a token in a JSON pointer
+pub fn escape_pointer_token(token: &str) -> String {
+    token.replace('~', "~0").replace('/', "~1")
+}
+
+/// Recursive walker over the characters of a JSON document
+struct Walker {
+    /// The characters of the document
+    chars: Vec<char>,
+
+    /// The index of the next character
+    index: usize,
+
+    /// The line of the next character
+    line: usize,
+
+    /// The column of the next character
+    column: usize,
+
+    /// The positions that have been recorded so far
+    positions: PositionIndex,
+}
+
+impl Walker {
+    /// Return the position of the next character
+    fn position(&self) -> Position {
+        Position::new(self.line, self.column)
+    }
+
+    /// Return the next character without consuming it
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.index).copied()
+    }
+
+    /// Consume the next character
+    fn next(&mut self) -> Option<char> {
+        let char = self.peek()?;
+        self.index += 1;
+
+        if char == '\n' {
+            self.line += 1;
+            self.column = 1;
+        } else {
+            self.column += 1;
+        }
+
+        Some(char)
+    }
+
+    /// Skip whitespace
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.next();
+        }
+    }
+
+    /// Walk a value and record its position under the given pointer
+    fn value(&mut self, pointer: &str) -> Option<()> {
+        self.skip_whitespace();
+        self.positions
+            .values
+            .insert(pointer.to_string(), self.position());
+
+        match self.peek()? {
+            '{' => self.object(pointer),
+            '[' => self.array(pointer),
+            '"' => self.string().map(|_| ()),
+            _ => {
+                while self
+                    .peek()
+                    .is_some_and(|char| !matches!(char, ',' | '}' | ']') && !char.is_whitespace())
+                {
+                    self.next();
+                }
+                Some(())
+            }
+        }
+    }
+
+    /// Walk an object and its members
+    fn object(&mut self, pointer: &str) -> Option<()> {
+        self.next();
+
+        loop {
+            self.skip_whitespace();
+            match self.peek()? {
+                '}' => {
+                    self.next();
+                    return Some(());
+                }
+                ',' => {
+                    self.next();
+                }
+                '"' => {
+                    let position = self.position();
+                    let key = self.string()?;
+                    let member = format!("{pointer}/{}", escape_pointer_token(&key));
+                    self.positions.keys.insert(member.clone(), position);
+
+                    self.skip_whitespace();
+                    if self.next()? != ':' {
+                        return None;
+                    }
+
+                    self.value(&member)?;
+                }
+                _ => return None,
+            }
+        }
+    }
+
+    /// Walk an array and its items
+    fn array(&mut self, pointer: &str) -> Option<()> {
+        self.next();
+        let mut item = 0;
+
+        loop {
+            self.skip_whitespace();
+            match self.peek()? {
+                ']' => {
+                    self.next();
+                    return Some(());
+                }
+                ',' => {
+                    self.next();
+                }
+                _ => {
+                    self.value(&format!("{pointer}/{item}"))?;
+                    item += 1;
+                }
+            }
+        }
+    }
+
+    /// Walk a string and return its unescaped content
+    fn string(&mut self) -> Option<String> {
+        self.next();
+        let mut string = String::new();
+
+        loop {
+            match self.next()? {
+                '"' => return Some(string),
+                '\\' => match self.next()? {
+                    'n' => string.push('\n'),
+                    't' => string.push('\t'),
+                    'r' => string.push('\r'),
+                    'b' => string.push('\u{8}'),
+                    'f' => string.push('\u{c}'),
+                    'u' => {
+                        let hex: String = (0..4).filter_map(|_| self.next()).collect();
+                        let code = u32::from_str_radix(&hex, 16).ok()?;
+                        string.push(char::from_u32(code).unwrap_or(char::REPLACEMENT_CHARACTER));
+                    }
+                    char => string.push(char),
+                },
+                char => string.push(char),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    const JSON: &str = indoc! {r#"
+        {
+          "url": "https://www.example.com",
+          "default_permissions": {
+            "issues": "write"
+          },
+          "default_events": ["issues", "push"],
+          "a/b": { "\"quoted\"": 1 }
+        }
+    "#};
+
+    #[test]
+    fn key_returns_position_of_key() {
+        let index = PositionIndex::new(JSON);
+
+        assert_eq!(
+            Some(Position::new(4, 5)),
+            index.key("/default_permissions/issues")
+        );
+    }
+
+    #[test]
+    fn value_returns_position_of_value() {
+        let index = PositionIndex::new(JSON);
+
+        assert_eq!(
+            Some(Position::new(4, 15)),
+            index.value("/default_permissions/issues")
+        );
+        assert_eq!(Some(Position::new(6, 32)), index.value("/default_events/1"));
+    }
+
+    #[test]
+    fn value_escapes_pointer_tokens() {
+        let index = PositionIndex::new(JSON);
+
+        assert_eq!(Some(Position::new(7, 12)), index.key("/a~1b/\"quoted\""));
+    }
+
+    #[test]
+    fn value_returns_none_for_missing_value() {
+        let index = PositionIndex::new(JSON);
+
+        assert_eq!(None, index.value("/missing"));
+    }
+
+    #[test]
+    fn trait_send() {
+        fn assert_send<T: Send>() {}
+        assert_send::<PositionIndex>();
+    }
+
+    #[test]
+    fn trait_sync() {
+        fn assert_sync<T: Sync>() {}
+        assert_sync::<PositionIndex>();
+    }
+
+    #[test]
+    fn trait_unpin() {
+        fn assert_unpin<T: Unpin>() {}
+        assert_unpin::<PositionIndex>();
+    }
+}
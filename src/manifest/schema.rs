@@ -0,0 +1,180 @@
+//! JSON Schema of the manifest format
+
+use schemars::gen::SchemaGenerator;
+use schemars::schema::{
+    InstanceType, Metadata, ObjectValidation, RootSchema, Schema, SchemaObject, SubschemaValidation,
+};
+use schemars::{schema_for, JsonSchema};
+use serde_json::Value;
+
+use super::{Event, Manifest, Permission};
+
+/// Generate the JSON Schema of the manifest format
+///
+/// The schema includes the catalog of permissions and events, which allows editors to autocomplete
+/// and validate manifest files. Fields that are unknown to this tool are sent to GitHub unchanged,
+/// but the schema rejects them so that editors flag misspelled fields.
+pub fn manifest_schema() -> RootSchema {
+    let mut schema = schema_for!(Manifest);
+    schema.schema.metadata().title = Some("GitHub App manifest".into());
+
+    let extends = SchemaObject {
+        metadata: Some(Box::new(Metadata {
+            description: Some(
+                "The path to a manifest that this manifest extends, relative to this file".into(),
+            ),
+            ..Default::default()
+        })),
+        instance_type: Some(InstanceType::String.into()),
+        ..Default::default()
+    };
+    schema
+        .schema
+        .object()
+        .properties
+        .insert("extends".into(), extends.into());
+
+    deny_additional_properties(&mut schema.schema);
+    if let Some(Schema::Object(hook_attributes)) = schema.definitions.get_mut("HookAttributes") {
+        deny_additional_properties(hook_attributes);
+    }
+
+    schema
+}
+
+/// Reject properties that are not listed in the schema of an object
+fn deny_additional_properties(schema: &mut SchemaObject) {
+    schema.object().additional_properties = Some(Box::new(Schema::Bool(false)));
+}
+
+/// Generate the schema of the permissions in a manifest
+///
+/// Every permission is a property of the object, whose value is one of the access levels that can
+/// be requested for the permission.
+pub fn permissions_schema(_generator: &mut SchemaGenerator) -> Schema {
+    let properties = Permission::ALL
+        .iter()
+        .map(|permission| {
+            let levels = permission
+                .access_levels()
+                .iter()
+                .map(|level| Value::String(level.to_string()))
+                .collect();
+
+            let schema = SchemaObject {
+                instance_type: Some(InstanceType::String.into()),
+                enum_values: Some(levels),
+                ..Default::default()
+            };
+
+            (permission.to_string(), schema.into())
+        })
+        .collect();
+
+    SchemaObject {
+        instance_type: Some(InstanceType::Object.into()),
+        object: Some(Box::new(ObjectValidation {
+            properties,
+            additional_properties: Some(Box::new(Schema::Bool(false))),
+            ..Default::default()
+        })),
+        ..Default::default()
+    }
+    .into()
+}
+
+impl JsonSchema for Event {
+    fn schema_name() -> String {
+        "Event".into()
+    }
+
+    /// Generate the schema of a webhook event
+    ///
+    /// Events that are not in the catalog are accepted, but the known events are listed so that
+    /// editors can suggest them.
+    fn json_schema(_generator: &mut SchemaGenerator) -> Schema {
+        let known_events = SchemaObject {
+            instance_type: Some(InstanceType::String.into()),
+            enum_values: Some(
+                Event::ALL
+                    .iter()
+                    .map(|event| Value::String(event.to_string()))
+                    .collect(),
+            ),
+            ..Default::default()
+        };
+        let other_events = SchemaObject {
+            instance_type: Some(InstanceType::String.into()),
+            ..Default::default()
+        };
+
+        SchemaObject {
+            metadata: Some(Box::new(Metadata {
+                description: Some("A webhook event to which the GitHub App subscribes".into()),
+                ..Default::default()
+            })),
+            subschemas: Some(Box::new(SubschemaValidation {
+                any_of: Some(vec![known_events.into(), other_events.into()]),
+                ..Default::default()
+            })),
+            ..Default::default()
+        }
+        .into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    use super::*;
+
+    fn schema() -> Value {
+        serde_json::to_value(manifest_schema()).unwrap()
+    }
+
+    #[test]
+    fn manifest_schema_requires_url() {
+        assert_eq!(json!(["url"]), schema()["required"]);
+    }
+
+    #[test]
+    fn manifest_schema_rejects_unknown_fields() {
+        let schema = schema();
+
+        assert_eq!(json!(false), schema["additionalProperties"]);
+        assert_eq!(
+            json!(false),
+            schema["definitions"]["HookAttributes"]["additionalProperties"]
+        );
+    }
+
+    #[test]
+    fn manifest_schema_allows_schema_and_extends() {
+        let schema = schema();
+
+        assert_eq!(json!("string"), schema["properties"]["extends"]["type"]);
+        assert!(schema["properties"]["$schema"].is_object());
+    }
+
+    #[test]
+    fn manifest_schema_lists_access_levels_of_permissions() {
+        let schema = schema();
+        let permissions = &schema["properties"]["default_permissions"]["properties"];
+
+        assert_eq!(json!(["write"]), permissions["workflows"]["enum"]);
+        assert_eq!(
+            json!(["read", "write", "admin"]),
+            permissions["organization_projects"]["enum"]
+        );
+    }
+
+    #[test]
+    fn manifest_schema_suggests_events() {
+        let schema = schema();
+        let events = &schema["definitions"]["Event"]["anyOf"][0]["enum"];
+
+        assert!(events.as_array().unwrap().contains(&json!("pull_request")));
+    }
+}
@@ -0,0 +1,113 @@
+//! Output of the commands for humans and machines
+//!
+//! By default, the commands print messages for humans to stdout. With the global `--json` flag,
+//! stdout is reserved for a single JSON object with the result of the command, and the messages
+//! for humans are printed to stderr instead. This allows scripts to parse the output of a command
+//! without having to filter it first.
+
+use std::fmt::Display;
+
+use anyhow::Error;
+use serde::Serialize;
+
+/// The output of a command
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
+pub struct Output {
+    /// Whether the result is printed as JSON
+    json: bool,
+
+    /// Whether stdout is reserved for data that the command prints itself, e.g. a manifest
+    stdout_reserved: bool,
+}
+
+impl Output {
+    /// Create a new output
+    pub fn new(json: bool) -> Self {
+        Self {
+            json,
+            stdout_reserved: false,
+        }
+    }
+
+    /// Print messages for humans to stderr, since the command prints its data to stdout
+    pub fn reserve_stdout(self) -> Self {
+        Self {
+            stdout_reserved: true,
+            ..self
+        }
+    }
+
+    /// Return whether the result is printed as JSON
+    pub fn is_json(&self) -> bool {
+        self.json
+    }
+
+    /// Print a message for humans
+    ///
+    /// Messages are printed to stderr when the result is printed as JSON, or when stdout is
+    /// reserved for the data of the command, so that stdout only contains the result.
+    pub fn message(&self, message: impl Display) {
+        if self.json || self.stdout_reserved {
+            eprintln!("{message}");
+        } else {
+            println!("{message}");
+        }
+    }
+
+    /// Print the result of the command as a JSON object, if requested
+    pub fn result<T: Serialize>(&self, result: &T) -> Result<(), Error> {
+        if self.json {
+            println!("{}", serde_json::to_string_pretty(result)?);
+        }
+
+        Ok(())
+    }
+
+    /// Print an event of a long-running command as a single line of JSON, if requested
+    ///
+    /// Commands that don't terminate on their own, like `tunnel`, report every event that they
+    /// handle as soon as it happens, so that the output can be processed line by line.
+    pub fn event<T: Serialize>(&self, event: &T) -> Result<(), Error> {
+        if self.json {
+            println!("{}", serde_json::to_string(event)?);
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_prints_for_humans() {
+        assert!(!Output::default().is_json());
+    }
+
+    #[test]
+    fn reserve_stdout_keeps_json_result() {
+        let output = Output::new(true).reserve_stdout();
+
+        assert!(output.is_json());
+        assert!(output.stdout_reserved);
+    }
+
+    #[test]
+    fn trait_send() {
+        fn assert_send<T: Send>() {}
+        assert_send::<Output>();
+    }
+
+    #[test]
+    fn trait_sync() {
+        fn assert_sync<T: Sync>() {}
+        assert_sync::<Output>();
+    }
+
+    #[test]
+    fn trait_unpin() {
+        fn assert_unpin<T: Unpin>() {}
+        assert_unpin::<Output>();
+    }
+}
@@ -0,0 +1,361 @@
+//! The apps that are registered in a run of the `register` command
+//!
+//! Projects that run several GitHub Apps side by side, e.g. a bot, a CI checker, and an OAuth app,
+//! can register all of them at once. The apps are either passed to the command as manifest files,
+//! or listed by name in the configuration files:
+//!
+//! ```toml
+//! [[apps]]
+//! name = "bot"
+//! manifest = "github/bot.json"
+//!
+//! [[apps]]
+//! name = "login"
+//! manifest = "github/login.json"
+//! env-file = "web/.env"
+//! ```
+
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Error};
+use getset::Getters;
+use serde::Deserialize;
+use typed_builder::TypedBuilder;
+
+use crate::register::RegisterArgs;
+
+/// An app that is listed by name in the configuration files
+///
+/// Options that are not set fall back to the options of the `register` command.
+#[derive(
+    Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Deserialize, Getters, TypedBuilder,
+)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct NamedApp {
+    /// The name of the app, which is used in the URLs of the local web server
+    #[builder(setter(into))]
+    #[getset(get = "pub")]
+    name: String,
+
+    /// The path to the app's manifest file
+    #[builder(default, setter(into))]
+    #[getset(get = "pub")]
+    manifest: Option<PathBuf>,
+
+    /// The profile of the manifest to register
+    #[builder(default, setter(into))]
+    #[getset(get = "pub")]
+    profile: Option<String>,
+
+    /// The organization that owns the app
+    #[builder(default, setter(into))]
+    #[getset(get = "pub")]
+    org: Option<String>,
+
+    /// The .env file to which the app's secrets are saved
+    #[builder(default, setter(into))]
+    #[getset(get = "pub")]
+    env_file: Option<PathBuf>,
+
+    /// The prefix of the app's variables in the .env file
+    #[builder(default, setter(into))]
+    #[getset(get = "pub")]
+    prefix: Option<String>,
+}
+
+impl NamedApp {
+    /// Resolve the relative paths of the app against a directory
+    pub fn resolve_paths(mut self, directory: &Path) -> Self {
+        self.manifest = self.manifest.map(|path| directory.join(path));
+        self.env_file = self.env_file.map(|path| directory.join(path));
+        self
+    }
+}
+
+/// An app that is registered by the command, with the options that apply to it
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Getters)]
+pub struct Target {
+    /// The name of the app
+    #[getset(get = "pub")]
+    name: String,
+
+    /// The path to the manifest file, or `None` to read the manifest from `Cargo.toml`
+    #[getset(get = "pub")]
+    manifest: Option<PathBuf>,
+
+    /// The profile of the manifest to register
+    #[getset(get = "pub")]
+    profile: Option<String>,
+
+    /// The organization that owns the app
+    #[getset(get = "pub")]
+    org: Option<String>,
+
+    /// The .env file to which the app's secrets are saved
+    #[getset(get = "pub")]
+    env_file: PathBuf,
+
+    /// The prefix of the app's variables in the .env file
+    #[getset(get = "pub")]
+    prefix: String,
+}
+
+/// Return the apps that are registered by the command, in the order in which they are registered
+///
+/// The apps that are selected with `--app` take precedence over the manifest files on the command
+/// line, which take precedence over the apps in the configuration files. Without any of them, a
+/// single app is registered from the manifest in `Cargo.toml`.
+///
+/// When more than one app is registered, the name of each app is added to the prefix of its
+/// variables, e.g. `GITHUB_BOT_APP_ID`, so that the apps can share a .env file.
+pub fn resolve_targets(args: &RegisterArgs) -> Result<Vec<Target>, Error> {
+    let apps = if !args.app().is_empty() {
+        args.app()
+            .iter()
+            .map(|name| find_app(args, name))
+            .collect::<Result<Vec<_>, _>>()?
+    } else if !args.manifest().is_empty() {
+        args.manifest()
+            .iter()
+            .map(|path| {
+                let name = path
+                    .file_stem()
+                    .map(|stem| stem.to_string_lossy().to_string())
+                    .unwrap_or_default();
+
+                NamedApp::builder()
+                    .name(name)
+                    .manifest(path.clone())
+                    .build()
+            })
+            .collect()
+    } else if !args.configured_apps().is_empty() {
+        args.configured_apps().clone()
+    } else {
+        vec![NamedApp::builder().name("app").build()]
+    };
+
+    let mut names = BTreeSet::new();
+    for app in &apps {
+        if !is_valid_name(app.name()) {
+            bail!(
+                "invalid app name `{}`, use only letters, digits, `-`, `_`, and `.`",
+                app.name()
+            );
+        }
+        if !names.insert(app.name()) {
+            bail!(
+                "the app `{}` is registered more than once, rename one of its manifest files",
+                app.name()
+            );
+        }
+    }
+
+    let several = apps.len() > 1;
+    let targets = apps
+        .into_iter()
+        .map(|app| {
+            let prefix = match (&app.prefix, several) {
+                (Some(prefix), _) => prefix.clone(),
+                (None, true) => format!("{}{}_", args.prefix(), variable_name(&app.name)),
+                (None, false) => args.prefix().clone(),
+            };
+
+            Target {
+                prefix,
+                profile: app.profile.or_else(|| args.profile().clone()),
+                org: app.org.or_else(|| args.org().clone()),
+                env_file: app.env_file.unwrap_or_else(|| args.env_file().clone()),
+                manifest: app.manifest,
+                name: app.name,
+            }
+        })
+        .collect();
+
+    Ok(targets)
+}
+
+/// Find an app in the configuration files by its name
+fn find_app(args: &RegisterArgs, name: &str) -> Result<NamedApp, Error> {
+    if let Some(app) = args.configured_apps().iter().find(|app| app.name() == name) {
+        return Ok(app.clone());
+    }
+
+    let names = args
+        .configured_apps()
+        .iter()
+        .map(|app| app.name().as_str())
+        .collect::<Vec<_>>();
+
+    if names.is_empty() {
+        bail!("unknown app `{name}`, no apps are listed in the configuration files");
+    }
+
+    bail!(
+        "unknown app `{name}`, the configured apps are {}",
+        names.join(", ")
+    )
+}
+
+/// Check that a name can be used in the URLs of the local web server
+fn is_valid_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+}
+
+/// Convert the name of an app to the part of a variable name, e.g. `CI_CHECKER` for `ci-checker`
+fn variable_name(name: &str) -> String {
+    name.chars()
+        .map(|c| match c.is_ascii_alphanumeric() {
+            true => c.to_ascii_uppercase(),
+            false => '_',
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use url::Url;
+
+    use super::*;
+
+    fn args(manifest: &[&str], app: &[&str], configured_apps: Vec<NamedApp>) -> RegisterArgs {
+        RegisterArgs::builder()
+            .manifest(manifest.iter().map(PathBuf::from).collect::<Vec<_>>())
+            .app(app.iter().map(|name| name.to_string()).collect::<Vec<_>>())
+            .configured_apps(configured_apps)
+            .github(Url::parse("https://api.github.com").unwrap())
+            .port(None)
+            .build()
+    }
+
+    fn configured_apps() -> Vec<NamedApp> {
+        vec![
+            NamedApp::builder()
+                .name("bot")
+                .manifest(Some("github/bot.json".into()))
+                .build(),
+            NamedApp::builder()
+                .name("ci-checker")
+                .manifest(Some("github/ci.json".into()))
+                .org(Some("otterbuild".into()))
+                .build(),
+            NamedApp::builder()
+                .name("login")
+                .env_file(Some("web/.env".into()))
+                .prefix(Some("OAUTH_".into()))
+                .build(),
+        ]
+    }
+
+    #[test]
+    fn resolve_targets_defaults_to_cargo_toml() {
+        let targets = resolve_targets(&args(&[], &[], Vec::new())).unwrap();
+
+        assert_eq!(1, targets.len());
+        assert_eq!(&None, targets[0].manifest());
+        assert_eq!("GITHUB_", targets[0].prefix());
+        assert_eq!(&PathBuf::from(".env"), targets[0].env_file());
+    }
+
+    #[test]
+    fn resolve_targets_keeps_prefix_of_single_manifest() {
+        let targets = resolve_targets(&args(&["github/bot.json"], &[], Vec::new())).unwrap();
+
+        assert_eq!(1, targets.len());
+        assert_eq!("bot", targets[0].name());
+        assert_eq!("GITHUB_", targets[0].prefix());
+    }
+
+    #[test]
+    fn resolve_targets_prefixes_variables_of_several_manifests() {
+        let args = args(
+            &["github/bot.json", "github/ci-checker.yml"],
+            &[],
+            Vec::new(),
+        );
+
+        let targets = resolve_targets(&args).unwrap();
+
+        assert_eq!(
+            vec!["GITHUB_BOT_", "GITHUB_CI_CHECKER_"],
+            targets.iter().map(Target::prefix).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            &Some(PathBuf::from("github/ci-checker.yml")),
+            targets[1].manifest()
+        );
+    }
+
+    #[test]
+    fn resolve_targets_rejects_manifests_with_same_name() {
+        let args = args(&["bot/app.json", "login/app.json"], &[], Vec::new());
+
+        let error = resolve_targets(&args).unwrap_err();
+
+        assert_eq!(
+            "the app `app` is registered more than once, rename one of its manifest files",
+            error.to_string()
+        );
+    }
+
+    #[test]
+    fn resolve_targets_registers_all_configured_apps() {
+        let targets = resolve_targets(&args(&[], &[], configured_apps())).unwrap();
+
+        assert_eq!(
+            vec!["bot", "ci-checker", "login"],
+            targets.iter().map(Target::name).collect::<Vec<_>>()
+        );
+        assert_eq!(&Some("otterbuild".into()), targets[1].org());
+        assert_eq!("OAUTH_", targets[2].prefix());
+        assert_eq!(&PathBuf::from("web/.env"), targets[2].env_file());
+    }
+
+    #[test]
+    fn resolve_targets_selects_configured_apps_by_name() {
+        let args = args(&["github/bot.json"], &["login"], configured_apps());
+
+        let targets = resolve_targets(&args).unwrap();
+
+        assert_eq!(1, targets.len());
+        assert_eq!("OAUTH_", targets[0].prefix());
+    }
+
+    #[test]
+    fn resolve_targets_errors_on_unknown_app() {
+        let error = resolve_targets(&args(&[], &["otter"], configured_apps())).unwrap_err();
+
+        assert_eq!(
+            "unknown app `otter`, the configured apps are bot, ci-checker, login",
+            error.to_string()
+        );
+    }
+
+    #[test]
+    fn variable_name_replaces_punctuation() {
+        assert_eq!("CI_CHECKER_V2", variable_name("ci-checker.v2"));
+    }
+
+    #[test]
+    fn trait_send() {
+        fn assert_send<T: Send>() {}
+        assert_send::<Target>();
+    }
+
+    #[test]
+    fn trait_sync() {
+        fn assert_sync<T: Sync>() {}
+        assert_sync::<Target>();
+    }
+
+    #[test]
+    fn trait_unpin() {
+        fn assert_unpin<T: Unpin>() {}
+        assert_unpin::<Target>();
+    }
+}
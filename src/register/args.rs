@@ -1,26 +1,82 @@
 //! Command-line arguments for the `register` subcommand
 
 use std::path::PathBuf;
+use std::time::Duration;
 
-use clap::Parser;
-use getset::{CopyGetters, Getters};
+use clap::{ArgAction, Parser};
+use getset::{CopyGetters, Getters, Setters};
 use typed_builder::TypedBuilder;
 use url::Url;
 
+use crate::manifest::ManifestOverrides;
+use crate::register::NamedApp;
+
 /// Command-line arguments for the `register` subcommand
 ///
-/// The `register` subcommand is used to register a new GitHub App from a manifest file. The command
-/// requires the path to the manifest file as an argument, and optionally accepts other arguments to
-/// customize the manifest.
+/// The `register` subcommand is used to register new GitHub Apps from manifest files. The command
+/// accepts the paths to the manifest files as arguments, and optionally accepts other arguments to
+/// customize the manifests. Without a path, the manifest is read from the `Cargo.toml` file.
 #[derive(
-    Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Parser, CopyGetters, Getters, TypedBuilder,
+    Clone,
+    Eq,
+    PartialEq,
+    Ord,
+    PartialOrd,
+    Hash,
+    Debug,
+    Parser,
+    CopyGetters,
+    Getters,
+    Setters,
+    TypedBuilder,
 )]
 pub struct RegisterArgs {
-    /// The path to the manifest file
+    /// The paths to the manifest files of the apps to register
+    ///
+    /// Defaults to the apps in the configuration files, or to the `[package.metadata.github-app]`
+    /// or `[workspace.metadata.github-app]` table in the `Cargo.toml` file of the current package.
+    /// The apps are registered one after another, and are named after their manifest files.
     #[arg()]
-    #[builder(setter(into))]
+    #[builder(default, setter(into))]
+    #[getset(get = "pub")]
+    manifest: Vec<PathBuf>,
+
+    /// The name of an app in the configuration files to register, instead of all of them
+    #[arg(long, value_name = "NAME")]
+    #[builder(default, setter(into))]
     #[getset(get = "pub")]
-    manifest: PathBuf,
+    app: Vec<String>,
+
+    /// The apps that are listed in the `[[apps]]` tables of the configuration files
+    #[arg(skip)]
+    #[builder(default, setter(into))]
+    #[getset(get = "pub", set = "pub")]
+    configured_apps: Vec<NamedApp>,
+
+    /// The profile of the manifest to register
+    ///
+    /// The overlay of the profile is merged into the manifest, e.g. `manifest.staging.json` for the
+    /// profile `staging` of `manifest.json`.
+    #[arg(long)]
+    #[builder(default, setter(into))]
+    #[getset(get = "pub")]
+    profile: Option<String>,
+
+    /// Overrides for the fields of the manifest
+    #[command(flatten)]
+    #[builder(default)]
+    #[getset(get = "pub")]
+    overrides: ManifestOverrides,
+
+    /// The directory with the project's templates for the pages of the registration process
+    ///
+    /// The directory can contain `form.html`, `success.html`, and `error.html` templates, and a
+    /// `context.toml` file with information about the project. Missing templates fall back to the
+    /// built-in templates.
+    #[arg(long, value_name = "DIR", default_value = ".github-dev-app/templates")]
+    #[builder(default = PathBuf::from(".github-dev-app/templates"), setter(into))]
+    #[getset(get = "pub")]
+    templates: PathBuf,
 
     /// The endpoint of the GitHub API
     #[arg(long, default_value_t = Url::parse("https://api.github.com").unwrap())]
@@ -28,9 +84,68 @@ pub struct RegisterArgs {
     #[getset(get = "pub")]
     github: Url,
 
+    /// The organization that owns the app, instead of the user who registers it
+    #[arg(long)]
+    #[builder(default, setter(into))]
+    #[getset(get = "pub")]
+    org: Option<String>,
+
     /// The port used by the embedded web server
     #[arg(long)]
     #[builder(setter(into))]
     #[getset(get_copy = "pub")]
     port: Option<u16>,
+
+    /// How long to wait for the app to be registered on GitHub, e.g. `90s` or `15m`
+    ///
+    /// The command fails if GitHub does not redirect back to the local web server in time, so
+    /// that scripts that run the command never hang indefinitely.
+    #[arg(
+        long,
+        value_name = "DURATION",
+        default_value = "10m",
+        value_parser = humantime::parse_duration
+    )]
+    #[builder(default = Duration::from_secs(600))]
+    #[getset(get_copy = "pub")]
+    timeout: Duration,
+
+    /// The URL of a smee.io channel that receives the app's webhooks
+    ///
+    /// The channel is set as the webhook URL in the manifest. Run the `tunnel` command to forward
+    /// the webhooks from the channel to a local server.
+    #[arg(long)]
+    #[builder(default, setter(into))]
+    #[getset(get = "pub")]
+    tunnel: Option<Url>,
+
+    /// The .env file to which the app's secrets are saved
+    #[arg(long, default_value = ".env")]
+    #[builder(default = PathBuf::from(".env"), setter(into))]
+    #[getset(get = "pub")]
+    env_file: PathBuf,
+
+    /// The prefix of the variables in the .env file, e.g. `STAGING_` for `STAGING_APP_ID`
+    ///
+    /// When several apps are registered, the name of each app is appended to the prefix, e.g.
+    /// `GITHUB_BOT_` for the app `bot`.
+    #[arg(long, default_value = "GITHUB_")]
+    #[builder(default = String::from("GITHUB_"), setter(into))]
+    #[getset(get = "pub")]
+    prefix: String,
+
+    /// Print links instead of opening the registration form and installation page in the browser
+    ///
+    /// This is useful on remote machines, e.g. in a dev container, where no browser is available.
+    #[arg(
+        long,
+        action = ArgAction::Set,
+        num_args = 0..=1,
+        require_equals = true,
+        default_value = "false",
+        default_missing_value = "true"
+    )]
+    #[builder(default)]
+    #[getset(get_copy = "pub")]
+    no_browser: bool,
 }
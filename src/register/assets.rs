@@ -0,0 +1,24 @@
+//! Static assets of the registration form
+//!
+//! The stylesheet and script of the form are embedded into the binary and served by the local web
+//! server, so that the form works without network access and does not load third-party code into a
+//! page that carries the manifest.
+
+use axum::http::header::CONTENT_TYPE;
+use axum::response::IntoResponse;
+
+/// The stylesheet of the registration form
+const FORM_CSS: &str = include_str!("../../assets/form.css");
+
+/// The script of the registration form
+const FORM_JS: &str = include_str!("../../assets/form.js");
+
+/// Serve the stylesheet of the registration form
+pub async fn form_css() -> impl IntoResponse {
+    ([(CONTENT_TYPE, "text/css; charset=utf-8")], FORM_CSS)
+}
+
+/// Serve the script of the registration form
+pub async fn form_js() -> impl IntoResponse {
+    ([(CONTENT_TYPE, "text/javascript; charset=utf-8")], FORM_JS)
+}
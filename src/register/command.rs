@@ -1,26 +1,37 @@
 //! Command to register a new GitHub App
 
 use std::env::var;
-use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
 
-use anyhow::{Context, Error};
+use anyhow::{bail, Context, Error};
 use async_trait::async_trait;
-use reqwest::Client;
+use reqwest::{Method, StatusCode};
+use serde_json::json;
+use tokio::signal::ctrl_c;
+use tokio::time::sleep;
+use tracing::{debug, trace};
 use url::Url;
 
 use crate::cli::Args;
+use crate::env::save_to_env;
+use crate::error::{CliError, Details};
+use crate::github::{GitHubClient, ResponseError};
+use crate::output::Output;
 use crate::Execute;
 
-use super::app::App;
-use super::env::save_to_env;
-use super::server::start_background_web_server;
+use super::apps::{resolve_targets, Target};
+use super::conversion::{Conversion, RegisterResult, RegisterResults};
+use super::form::web_url;
+use super::server::{start_background_web_server, WebServer};
+use super::templates::Templates;
 use super::RegisterArgs;
 
 /// Register a new GitHub App
 ///
-/// This command is used to register a new GitHub App from a manifest file. The manifest file must
-/// be provided as an argument to the command. The command will parse the manifest, optionally
-/// customize it, and then register the app with GitHub.
+/// This command is used to register new GitHub Apps from manifest files. The manifest files are
+/// provided as arguments to the command, listed in the configuration files, or read from
+/// `Cargo.toml`. The command will parse each manifest, optionally customize it, and then register
+/// the apps with GitHub one after another.
 #[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
 pub struct RegisterCommand<'a> {
     /// The arguments for the command
@@ -33,104 +44,467 @@ impl<'a> RegisterCommand<'a> {
         Self { args }
     }
 
+    /// Return the developer settings in which the apps of the user or organization are listed
+    ///
+    /// The settings are on the web interface of the GitHub instance that `--github` points to.
+    fn developer_settings_url(&self, org: Option<&str>) -> Url {
+        let path = match org {
+            Some(org) => format!("/organizations/{org}/settings/apps"),
+            None => "/settings/apps".into(),
+        };
+
+        web_url(self.args.github().clone())
+            .join(&path)
+            .expect("failed to parse URL path of the developer settings")
+    }
+
+    /// Register the apps one after another, while accepting their callbacks in any order
+    ///
+    /// The apps that have been registered are stored in `results`, so that they can be reported
+    /// when a later app fails.
+    async fn register_apps(
+        &self,
+        client: &GitHubClient,
+        server: &mut WebServer,
+        targets: &[Target],
+        results: &mut [Option<RegisterResult>],
+        output: Output,
+    ) -> Result<(), Error> {
+        let several = targets.len() > 1;
+
+        let mut opened = None;
+        while let Some(next) = results.iter().position(Option::is_none) {
+            // Open a browser to start the registration process, unless its form is already open
+            if opened != Some(next) {
+                let app = several.then(|| targets[next].name().as_str());
+                self.open_registration_form(&server.url(next), app, output)?;
+                opened = Some(next);
+            }
+
+            // Wait for the user to be redirected back to the local server with a temporary code
+            let timeout = self.args.timeout();
+            let registration = tokio::select! {
+                registration = server.next_registration() => registration
+                    .context("failed to receive temporary code from internal channel")?,
+                _ = sleep(timeout) => {
+                    bail!(CliError::Timeout(humantime::format_duration(timeout).to_string()));
+                }
+                _ = ctrl_c() => {
+                    bail!(CliError::Interrupted(format!(
+                        "interrupted before GitHub redirected back, no callback was received. If \
+                         you created the app on GitHub, delete it at {}",
+                        self.developer_settings_url(targets[next].org().as_deref())
+                    )));
+                }
+            };
+            let target = &targets[registration.app];
+
+            // A form that is submitted again creates another app, which must not replace the
+            // secrets of the app that has already been registered
+            if let Some(registered) = &results[registration.app] {
+                let message = format!(
+                    "the app `{}` has already been registered as {}, delete the app that was just \
+                     created at {}",
+                    target.name(),
+                    registered.name,
+                    self.developer_settings_url(target.org().as_deref())
+                );
+                output.message(format!("Warning: {message}"));
+                let _ = registration.outcome.send(Err(message));
+                continue;
+            }
+
+            // Exchange the temporary code for the app secrets and save them to the .env file. The
+            // code can only be exchanged once, so the first Ctrl-C does not abort the exchange.
+            let completion = complete_registration(
+                client,
+                &registration.code,
+                target.env_file(),
+                target.prefix(),
+            );
+            tokio::pin!(completion);
+            let result = tokio::select! {
+                result = &mut completion => result,
+                _ = ctrl_c() => {
+                    output.message(
+                        "Saving the app's secrets, press Ctrl-C again to abort and lose them",
+                    );
+                    tokio::select! {
+                        result = &mut completion => result,
+                        _ = ctrl_c() => Err(CliError::SecretsNotSaved(
+                            "interrupted while saving the app's secrets".into()
+                        ).into()),
+                    }
+                }
+            }
+            .context(
+                "the GitHub App was created, but its secrets were not saved to the .env file. \
+                 Delete the app in the developer settings on GitHub and register it again",
+            );
+
+            // Show the outcome in the browser before the server is shut down
+            let result =
+                result.map(|(conversion, env_file)| register_result(&conversion, target, env_file));
+            let outcome = match &result {
+                Ok(result) => Ok(result.clone()),
+                Err(error) => Err(format!("{error:#}")),
+            };
+            let _ = registration.outcome.send(outcome);
+            let result = result?;
+
+            if several {
+                output.message(format!(
+                    "Registered GitHub App {} and saved its secrets to {} with the prefix {}",
+                    result.name,
+                    result.env_file.display(),
+                    result.prefix
+                ));
+            } else {
+                output.message(format!(
+                    "Registered GitHub App {} and saved its secrets to {}",
+                    result.name,
+                    result.env_file.display()
+                ));
+            }
+
+            // Open the page to install the app
+            self.open_installation_page(&result.installation_url, output)?;
+
+            results[registration.app] = Some(result);
+        }
+
+        Ok(())
+    }
+
     /// Open the form that starts the registration process
     ///
     /// This method opens the form that starts the registration process in the user's default web
     /// browser. The form sends the manifest to register a new GitHub App, allowing the user to
     /// customize the name. After the user registers the app, they will be redirected back to the
-    /// local web server to complete the registration process.
-    fn open_registration_form(&self, addr: &SocketAddr) -> Result<(), Error> {
+    /// local web server to complete the registration process. The name of the app is shown when
+    /// several apps are registered.
+    fn open_registration_form(
+        &self,
+        url: &str,
+        app: Option<&str>,
+        output: Output,
+    ) -> Result<(), Error> {
+        if self.args.no_browser() {
+            let app = app.map(|app| format!(" `{app}`")).unwrap_or_default();
+            output.message(format!(
+                "Open {url} in your browser to register the GitHub App{app}"
+            ));
+            return Ok(());
+        }
+
         // Skip opening the browser if running in CI
         if var("CI").is_ok() {
             return Ok(());
         }
 
-        open::that(format!("http://{}:{}", addr.ip(), addr.port()))
-            .context("failed to open browser to start registration process")
+        open::that(url).context("failed to open browser to start registration process")
     }
 
     /// Open the page to install the app
     ///
     /// After a new app has been registered, the user can install it in their GitHub account. This
     /// method opens the GitHub settings to install the app.
-    fn open_installation_page(&self, app: &App) -> Result<(), Error> {
+    fn open_installation_page(&self, url: &str, output: Output) -> Result<(), Error> {
+        if self.args.no_browser() {
+            output.message(format!("Install the GitHub App at {url}"));
+            return Ok(());
+        }
+
         // Skip opening the browser if running in CI
         if var("CI").is_ok() {
             return Ok(());
         }
 
-        open::that(format!(
-            "https://github.com/settings/apps/{}/installations",
-            app.name()
-        ))
-        .context("failed to open browser to start installation process")
+        open::that(url).context("failed to open browser to start installation process")
     }
 }
 
 #[async_trait]
 impl<'a> Execute for RegisterCommand<'a> {
-    async fn execute(&self, _global_args: &Args) -> Result<(), Error> {
-        let (addr, mut receiver) = start_background_web_server(
-            self.args.manifest(),
-            self.args.github().clone(),
-            self.args.port(),
-        )
-        .await?;
+    async fn execute(&self, global_args: &Args) -> Result<(), Error> {
+        let output = global_args.output();
+        let client = GitHubClient::new(self.args.github(), &global_args.http_client()?);
+        let templates = Templates::load(self.args.templates())?;
 
-        // Open a browser to start the registration process
-        self.open_registration_form(&addr)?;
+        let targets = resolve_targets(self.args)?;
+        let several = targets.len() > 1;
 
-        // Wait for the user to be redirected back to the local server with a temporary code
-        let temporary_code = receiver
-            .recv()
-            .await
-            .context("failed to receive temporary code from internal channel")?;
+        let mut server = start_background_web_server(self.args, &targets, templates).await?;
 
-        // Exchange the temporary code for the app secrets
-        let app = exchange_temporary_code(self.args.github(), &temporary_code).await?;
+        let mut results: Vec<Option<RegisterResult>> = vec![None; targets.len()];
+        let registered = self
+            .register_apps(&client, &mut server, &targets, &mut results, output)
+            .await;
+        server.shutdown().await?;
 
-        // Save secrets and private key to the .env file
-        save_to_env(&app)?;
+        // Apps that were registered before a failure keep their secrets, and are reported with it
+        let mut apps = results.into_iter().flatten().collect::<Vec<_>>();
+        if let Err(error) = registered {
+            return Err(match apps.is_empty() {
+                true => error,
+                false => error.context(registered_before_failure(&apps, targets.len())),
+            });
+        }
 
-        // Open the page to install the app
-        self.open_installation_page(&app)?;
+        if several {
+            output.result(&RegisterResults { apps })?;
+        } else if let Some(result) = apps.pop() {
+            output.result(&result)?;
+        }
 
         Ok(())
     }
 }
 
+/// Complete the registration of the app
+///
+/// The temporary code is exchanged for the app's secrets, which are then saved to the .env file.
+/// Returns the app and the path of the .env file.
+async fn complete_registration(
+    client: &GitHubClient,
+    code: &str,
+    env_file: &Path,
+    prefix: &str,
+) -> Result<(Conversion, PathBuf), Error> {
+    let conversion = exchange_temporary_code(client, code).await?;
+    let env_file = save_to_env(conversion.app(), env_file, prefix)?;
+
+    Ok((conversion, env_file))
+}
+
+/// Summarize the registered app for the user
+fn register_result(conversion: &Conversion, target: &Target, env_file: PathBuf) -> RegisterResult {
+    let app = conversion.app();
+
+    RegisterResult {
+        id: app.id().get(),
+        name: app.name().to_string(),
+        slug: conversion.slug().clone(),
+        owner: conversion
+            .owner()
+            .as_ref()
+            .map(|owner| owner.login().clone()),
+        html_url: conversion.html_url().clone(),
+        installation_url: installation_url(conversion, target.org().as_deref()),
+        env_file,
+        prefix: target.prefix().clone(),
+    }
+}
+
+/// Return the page on GitHub to install the app
+///
+/// Apps that are owned by an organization are managed in the organization's settings.
+fn installation_url(conversion: &Conversion, org: Option<&str>) -> String {
+    let app = conversion
+        .slug()
+        .clone()
+        .unwrap_or_else(|| conversion.app().name().to_string());
+
+    match org {
+        Some(org) => {
+            format!("https://github.com/organizations/{org}/settings/apps/{app}/installations")
+        }
+        None => format!("https://github.com/settings/apps/{app}/installations"),
+    }
+}
+
+/// Describe the apps that were registered before a later app failed
+///
+/// The apps are included in the JSON object of the error, so that scripts can pick up the secrets
+/// that were saved.
+fn registered_before_failure(apps: &[RegisterResult], total: usize) -> Details {
+    let saved = apps
+        .iter()
+        .map(|app| format!("{} to {}", app.name, app.env_file.display()))
+        .collect::<Vec<_>>();
+
+    Details {
+        message: format!(
+            "registered {} of {total} apps before the failure, and saved the secrets of {}. \
+             Register only the remaining apps when you run the command again",
+            apps.len(),
+            saved.join(", ")
+        ),
+        details: json!({ "apps": apps }),
+    }
+}
+
 /// Exchange a temporary code for the app secrets
 ///
 /// This function exchanges a temporary code for the app secrets. The temporary code is provided by
 /// GitHub after the user registers the app. The function sends the temporary code to GitHub and
-/// receives the app's id, secrets, and private key in return.
-async fn exchange_temporary_code(github: &Url, code: &str) -> Result<App, Error> {
-    let url = github.join(&format!("app-manifests/{code}/conversions"))?;
-
-    let response = Client::new()
-        .post(url)
-        .header("User-Agent", "otterbuild/github-dev-app")
-        .send()
-        .await
-        .context("failed to convert temporary code")?;
+/// receives the app's id, secrets, and private key in return. GitHub rejects codes that have
+/// expired or have already been exchanged.
+async fn exchange_temporary_code(client: &GitHubClient, code: &str) -> Result<Conversion, Error> {
+    let request = client.request(Method::POST, &format!("app-manifests/{code}/conversions"))?;
 
-    if !response.status().is_success() {
-        return Err(anyhow::anyhow!(
-            "failed to convert temporary code with error {}",
-            response.text().await?
-        ));
-    }
+    let response = client.send(request).await.map_err(|error| {
+        let is_expired = error.downcast_ref::<ResponseError>().is_some_and(|error| {
+            matches!(
+                error.status,
+                StatusCode::NOT_FOUND | StatusCode::UNPROCESSABLE_ENTITY
+            )
+        });
 
-    response
-        .json()
+        if is_expired {
+            error.context(CliError::CodeExpired)
+        } else {
+            error.context("failed to convert temporary code")
+        }
+    })?;
+
+    let body = response
+        .text()
         .await
-        .context("failed to parse conversion response")
+        .context("failed to read conversion response")?;
+    trace!(body = %body, "received conversion response");
+
+    let conversion: Conversion =
+        serde_json::from_str(&body).context("failed to parse conversion response")?;
+    debug!(id = %conversion.app().id(), slug = ?conversion.slug(), "converted temporary code");
+
+    Ok(conversion)
 }
 
 #[cfg(test)]
 mod tests {
+    use mockito::Server;
+    use pretty_assertions::assert_eq;
+
+    use crate::github::HttpClient;
+
     use super::*;
 
+    fn client(url: &str) -> GitHubClient {
+        GitHubClient::new(
+            &Url::parse(url).unwrap(),
+            &HttpClient::new(None, 0).unwrap(),
+        )
+    }
+
+    fn args(github: &str) -> RegisterArgs {
+        RegisterArgs::builder()
+            .manifest(Vec::new())
+            .app(Vec::new())
+            .configured_apps(Vec::new())
+            .github(Url::parse(github).unwrap())
+            .port(None)
+            .build()
+    }
+
+    #[test]
+    fn developer_settings_url_uses_github_com_for_its_api() {
+        let args = args("https://api.github.com");
+        let command = RegisterCommand::new(&args);
+
+        assert_eq!(
+            "https://github.com/organizations/octocat/settings/apps",
+            command.developer_settings_url(Some("octocat")).as_str()
+        );
+        assert_eq!(
+            "https://github.com/settings/apps",
+            command.developer_settings_url(None).as_str()
+        );
+    }
+
+    #[test]
+    fn developer_settings_url_uses_host_of_enterprise_server() {
+        let args = args("https://github.example.com/api/v3");
+        let command = RegisterCommand::new(&args);
+
+        assert_eq!(
+            "https://github.example.com/organizations/octocat/settings/apps",
+            command.developer_settings_url(Some("octocat")).as_str()
+        );
+    }
+
+    #[test]
+    fn registered_before_failure_lists_saved_secrets() {
+        let app = RegisterResult {
+            id: 1,
+            name: "octoapp-bot".into(),
+            slug: Some("octoapp-bot".into()),
+            owner: None,
+            html_url: None,
+            installation_url: "https://github.com/settings/apps/octoapp-bot/installations".into(),
+            env_file: PathBuf::from(".env"),
+            prefix: "GITHUB_BOT_".into(),
+        };
+
+        let details = registered_before_failure(&[app], 2);
+
+        assert_eq!(
+            "registered 1 of 2 apps before the failure, and saved the secrets of octoapp-bot to \
+             .env. Register only the remaining apps when you run the command again",
+            details.message
+        );
+        assert_eq!("GITHUB_BOT_", details.details["apps"][0]["prefix"]);
+    }
+
+    #[tokio::test]
+    async fn exchange_temporary_code_does_not_retry_server_errors() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("POST", "/app-manifests/otters/conversions")
+            .with_status(502)
+            .expect(1)
+            .create_async()
+            .await;
+        let client = GitHubClient::new(
+            &Url::parse(&server.url()).unwrap(),
+            &HttpClient::new(None, 1).unwrap(),
+        );
+
+        let error = exchange_temporary_code(&client, "otters")
+            .await
+            .unwrap_err();
+
+        mock.assert_async().await;
+        assert_eq!(
+            "failed to convert temporary code: GitHub responded with 502 Bad Gateway: ",
+            format!("{error:#}")
+        );
+    }
+
+    #[tokio::test]
+    async fn exchange_temporary_code_reports_expired_code() {
+        let mut server = Server::new_async().await;
+        let _mock = server
+            .mock("POST", "/app-manifests/otters/conversions")
+            .with_status(404)
+            .with_body(r#"{"message":"Not Found"}"#)
+            .create_async()
+            .await;
+
+        let error = exchange_temporary_code(&client(&server.url()), "otters")
+            .await
+            .unwrap_err();
+
+        assert_eq!(
+            Some(&CliError::CodeExpired),
+            error.downcast_ref::<CliError>()
+        );
+        assert_eq!(
+            "the temporary code from GitHub has expired or has already been used: GitHub \
+             responded with 404 Not Found: Not Found",
+            format!("{error:#}")
+        );
+    }
+
+    #[tokio::test]
+    async fn exchange_temporary_code_reports_network_error() {
+        let error = exchange_temporary_code(&client("http://127.0.0.1:1"), "otters")
+            .await
+            .unwrap_err();
+
+        assert_eq!(Some(&CliError::Network), error.downcast_ref::<CliError>());
+    }
+
     #[test]
     fn trait_send() {
         fn assert_send<T: Send>() {}
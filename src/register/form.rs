@@ -1,41 +1,338 @@
 //! The form for starting the registration process
 
 use askama_axum::Template;
+use serde::Serialize;
 use url::Url;
 
-use crate::manifest::SerializedManifest;
+use crate::manifest::{Event, Manifest, Permission, SerializedManifest};
+use crate::register::templates::ProjectContext;
 
 /// The form for starting the registration process
 ///
 /// To register a new GitHub App, the user must send a POST request to the server with the manifest.
 /// This is done by submitting a form in the browser so that the user is authenticated with GitHub.
-/// This struct defines the fields of the form and renders the template.
-#[derive(Clone, Eq, PartialEq, Debug, Template)]
-#[template(path = "form.html", escape = "none")]
+/// The form shows the name, webhook, permissions, and events of the app as controls, so that they
+/// can be reviewed and adjusted before the manifest is submitted. This struct defines the fields of
+/// the form and renders the template. The fields are also the context of a project's own template.
+#[derive(Clone, Eq, PartialEq, Debug, Serialize, Template)]
+#[template(path = "form.html")]
 pub struct Form {
     /// The endpoint of the GitHub API
     form_endpoint: Url,
 
-    /// The manifest for the GitHub App
-    manifest: SerializedManifest,
+    /// The manifest for the GitHub App, as JSON that can be embedded in a script element
+    manifest: String,
+
+    /// The name of the app
+    name: String,
+
+    /// The description of the app
+    description: String,
+
+    /// The homepage of the app
+    url: String,
+
+    /// The URL to which the app's webhooks are delivered
+    webhook_url: String,
+
+    /// Whether the app's webhook is active
+    webhook_active: bool,
+
+    /// Whether the app is available to the public
+    public: bool,
+
+    /// The controls for the permissions of the app
+    permissions: Vec<PermissionControl>,
+
+    /// The controls for the events of the app
+    events: Vec<EventControl>,
+
+    /// Whether the edits can be saved to the manifest file
+    can_save: bool,
+
+    /// Information about the project
+    project: ProjectContext,
+}
+
+/// The control for a permission on the form
+#[derive(Clone, Eq, PartialEq, Debug, Serialize)]
+struct PermissionControl {
+    /// The name of the permission
+    name: String,
+
+    /// The access levels that can be requested for the permission
+    levels: Vec<String>,
+
+    /// The requested access level, or an empty string if the permission is not requested
+    selected: String,
+}
+
+/// The control for a webhook event on the form
+#[derive(Clone, Eq, PartialEq, Debug, Serialize)]
+struct EventControl {
+    /// The name of the event
+    name: String,
+
+    /// The permissions of which one is required to receive the event, separated by spaces
+    required: String,
+
+    /// Whether the app subscribes to the event
+    checked: bool,
 }
 
 impl Form {
     /// Create a new instance of the form
-    pub fn new(github: Url, manifest: SerializedManifest) -> Self {
-        let base_url = if github.domain() == Some("api.github.com") {
-            Url::parse("https://github.com").expect("failed to parse hard-coded URL")
-        } else {
-            github
-        };
+    ///
+    /// The form registers the app for the given organization, or for the user who submits it.
+    pub fn new(
+        github: Url,
+        org: Option<&str>,
+        manifest: &Manifest,
+        can_save: bool,
+        project: ProjectContext,
+    ) -> Self {
+        let base_url = web_url(github);
 
+        let path = match org {
+            Some(org) => format!("/organizations/{org}/settings/apps/new"),
+            None => "/settings/apps/new".into(),
+        };
         let form_endpoint = base_url
-            .join("/settings/apps/new")
-            .expect("failed to parse hard-coded URL path");
+            .join(&path)
+            .expect("failed to parse URL path of the form");
+
+        let serialized_manifest = SerializedManifest::try_from(manifest.clone())
+            .map(|manifest| manifest.get().to_string())
+            .unwrap_or_else(|_| "{}".into());
+
+        let hook_attributes = manifest.hook_attributes().as_ref();
 
         Self {
             form_endpoint,
-            manifest,
+            // Escape the JSON so that it cannot close the script element that embeds it
+            manifest: serialized_manifest.replace('<', "\\u003c"),
+            name: manifest
+                .name()
+                .as_ref()
+                .map(|name| name.get().to_string())
+                .unwrap_or_default(),
+            description: manifest
+                .description()
+                .as_ref()
+                .map(|description| description.get().to_string())
+                .unwrap_or_default(),
+            url: manifest.url().get().to_string(),
+            webhook_url: hook_attributes
+                .map(|hook_attributes| hook_attributes.url().get().to_string())
+                .unwrap_or_default(),
+            webhook_active: hook_attributes
+                .and_then(|hook_attributes| *hook_attributes.active())
+                .unwrap_or(true),
+            public: manifest.public().unwrap_or_default(),
+            permissions: permission_controls(manifest),
+            events: event_controls(manifest),
+            can_save,
+            project,
         }
     }
+
+    /// Return the endpoint to which the form is submitted
+    pub fn form_endpoint(&self) -> &Url {
+        &self.form_endpoint
+    }
+}
+
+/// Create the controls for all permissions
+fn permission_controls(manifest: &Manifest) -> Vec<PermissionControl> {
+    let mut permissions = Permission::ALL.to_vec();
+    permissions.sort();
+
+    permissions
+        .into_iter()
+        .map(|permission| PermissionControl {
+            name: permission.to_string(),
+            levels: permission
+                .access_levels()
+                .iter()
+                .map(ToString::to_string)
+                .collect(),
+            selected: manifest
+                .default_permissions()
+                .as_ref()
+                .and_then(|permissions| permissions.get(&permission))
+                .map(ToString::to_string)
+                .unwrap_or_default(),
+        })
+        .collect()
+}
+
+/// Create the controls for all events in the catalog and the unknown events in the manifest
+fn event_controls(manifest: &Manifest) -> Vec<EventControl> {
+    let subscribed = manifest.default_events().clone().unwrap_or_default();
+    let unknown_events = subscribed.iter().filter(|event| event.is_unknown());
+
+    Event::ALL
+        .iter()
+        .chain(unknown_events)
+        .map(|event| EventControl {
+            name: event.to_string(),
+            required: event
+                .required_permissions()
+                .unwrap_or_default()
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(" "),
+            checked: subscribed.contains(event),
+        })
+        .collect()
+}
+
+/// Return the web interface of GitHub for the endpoint of its API
+///
+/// The API of github.com has its own domain, while GitHub Enterprise Server serves the web
+/// interface and the API from the same host.
+pub fn web_url(github: Url) -> Url {
+    if github.domain() == Some("api.github.com") {
+        Url::parse("https://github.com").expect("failed to parse hard-coded URL")
+    } else {
+        github
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use pretty_assertions::assert_eq;
+
+    use crate::manifest::{AccessLevel, HookAttributes};
+
+    use super::*;
+
+    fn manifest() -> Manifest {
+        let mut manifest = Manifest::builder()
+            .url("https://example.com".into())
+            .hook_attributes(
+                HookAttributes::builder()
+                    .url("https://smee.io/otters")
+                    .active(false)
+                    .build(),
+            )
+            .default_events(vec![Event::Issues, Event::from("future_event")])
+            .default_permissions(BTreeMap::from([(Permission::Issues, AccessLevel::Write)]))
+            .build();
+        manifest.set_name(Some("</script><b>octoapp</b>".into()));
+
+        manifest
+    }
+
+    #[test]
+    fn new_uses_github_for_api_endpoint() {
+        let form = Form::new(
+            Url::parse("https://api.github.com").unwrap(),
+            None,
+            &manifest(),
+            true,
+            ProjectContext::default(),
+        );
+
+        assert_eq!(
+            "https://github.com/settings/apps/new",
+            form.form_endpoint.as_str()
+        );
+    }
+
+    #[test]
+    fn new_registers_app_for_organization() {
+        let form = Form::new(
+            Url::parse("https://github.example.com/api/v3").unwrap(),
+            Some("otterbuild"),
+            &manifest(),
+            true,
+            ProjectContext::default(),
+        );
+
+        assert_eq!(
+            "https://github.example.com/organizations/otterbuild/settings/apps/new",
+            form.form_endpoint.as_str()
+        );
+    }
+
+    #[test]
+    fn new_escapes_embedded_manifest() {
+        let form = Form::new(
+            Url::parse("https://api.github.com").unwrap(),
+            None,
+            &manifest(),
+            true,
+            ProjectContext::default(),
+        );
+
+        assert!(!form.manifest.contains("</script>"));
+        assert_eq!(
+            "</script><b>octoapp</b>",
+            serde_json::from_str::<serde_json::Value>(&form.manifest).unwrap()["name"]
+        );
+    }
+
+    #[test]
+    fn new_selects_permissions_and_events() {
+        let form = Form::new(
+            Url::parse("https://api.github.com").unwrap(),
+            None,
+            &manifest(),
+            true,
+            ProjectContext::default(),
+        );
+
+        let issues = form
+            .permissions
+            .iter()
+            .find(|permission| permission.name == "issues")
+            .unwrap();
+        assert_eq!("write", issues.selected);
+
+        let checked: Vec<&str> = form
+            .events
+            .iter()
+            .filter(|event| event.checked)
+            .map(|event| event.name.as_str())
+            .collect();
+        assert_eq!(vec!["issues", "future_event"], checked);
+        assert!(!form.webhook_active);
+    }
+
+    #[test]
+    fn render_escapes_values_in_controls() {
+        let form = Form::new(
+            Url::parse("https://api.github.com").unwrap(),
+            None,
+            &manifest(),
+            true,
+            ProjectContext::default(),
+        );
+
+        let html = form.render().unwrap();
+
+        assert!(html.contains("&lt;/script&gt;&lt;b&gt;octoapp&lt;/b&gt;"));
+    }
+
+    #[test]
+    fn trait_send() {
+        fn assert_send<T: Send>() {}
+        assert_send::<Form>();
+    }
+
+    #[test]
+    fn trait_sync() {
+        fn assert_sync<T: Sync>() {}
+        assert_sync::<Form>();
+    }
+
+    #[test]
+    fn trait_unpin() {
+        fn assert_unpin<T: Unpin>() {}
+        assert_unpin::<Form>();
+    }
 }
@@ -1,11 +1,16 @@
 //! Register a new GitHub App from a manifest
 
+pub use self::apps::NamedApp;
 pub use self::args::*;
 pub use self::command::*;
 
-mod app;
+mod apps;
 mod args;
+mod assets;
 mod command;
-mod env;
+mod conversion;
 mod form;
+mod pages;
+mod review;
 mod server;
+mod templates;
@@ -0,0 +1,98 @@
+//! The pages that conclude the registration process
+
+use askama_axum::Template;
+use serde::Serialize;
+
+use crate::register::templates::ProjectContext;
+
+/// The page that is shown after the app has been registered
+///
+/// The page links to the installation of the app and lists the steps to set up the project, so that
+/// developers can continue with the onboarding of the project.
+#[derive(Clone, Eq, PartialEq, Debug, Serialize, Template)]
+#[template(path = "success.html")]
+pub struct SuccessPage {
+    /// The name of the app
+    pub app_name: String,
+
+    /// The page on GitHub to install the app
+    pub installation_url: String,
+
+    /// The file to which the app's secrets were written
+    pub env_file: String,
+
+    /// Information about the project
+    pub project: ProjectContext,
+}
+
+/// The page that is shown if the registration failed
+#[derive(Clone, Eq, PartialEq, Debug, Serialize, Template)]
+#[template(path = "error.html")]
+pub struct ErrorPage {
+    /// The reason why the registration failed
+    pub message: String,
+
+    /// Information about the project
+    pub project: ProjectContext,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn success_page_lists_setup_steps() {
+        let project: ProjectContext = toml::from_str(indoc::indoc! {r#"
+            name = "Octoapp"
+            readme = "https://example.com/README.md"
+            setup_steps = ["Run `cargo run`"]
+        "#})
+        .unwrap();
+
+        let html = SuccessPage {
+            app_name: "octoapp-dev".into(),
+            installation_url: "https://github.com/settings/apps/octoapp-dev/installations".into(),
+            env_file: ".env".into(),
+            project,
+        }
+        .render()
+        .unwrap();
+
+        assert!(html.contains("https://github.com/settings/apps/octoapp-dev/installations"));
+        assert!(html.contains("https://example.com/README.md"));
+        assert!(html.contains("Run `cargo run`"));
+    }
+
+    #[test]
+    fn error_page_escapes_message() {
+        let html = ErrorPage {
+            message: "<b>failed</b>".into(),
+            project: ProjectContext::default(),
+        }
+        .render()
+        .unwrap();
+
+        assert!(html.contains("&lt;b&gt;failed&lt;"));
+    }
+
+    #[test]
+    fn trait_send() {
+        fn assert_send<T: Send>() {}
+        assert_send::<SuccessPage>();
+        assert_send::<ErrorPage>();
+    }
+
+    #[test]
+    fn trait_sync() {
+        fn assert_sync<T: Sync>() {}
+        assert_sync::<SuccessPage>();
+        assert_sync::<ErrorPage>();
+    }
+
+    #[test]
+    fn trait_unpin() {
+        fn assert_unpin<T: Unpin>() {}
+        assert_unpin::<SuccessPage>();
+        assert_unpin::<ErrorPage>();
+    }
+}
@@ -0,0 +1,275 @@
+//! Write the edits from the review page back to the manifest file
+
+use std::path::Path;
+
+use anyhow::{anyhow, Context, Error};
+use serde_json::Value;
+
+use crate::manifest::{Format, Manifest, SerializedManifest};
+
+/// The fields of the manifest that can be edited on the review page
+const EDITABLE_FIELDS: [&str; 7] = [
+    "name",
+    "description",
+    "url",
+    "hook_attributes",
+    "public",
+    "default_permissions",
+    "default_events",
+];
+
+/// Write the edits from the review page back to a manifest file
+///
+/// Only the fields that were changed on the review page are written, so that fields with
+/// placeholders or values from the command line are kept as they are in the file. The file is
+/// serialized again in its format, which removes its comments. Returns a message for the user.
+///
+/// Set `overlay` if the file is the overlay of a profile, so that removed fields are set to `null`
+/// instead of being inherited from the base manifest. TOML has no `null`, so fields cannot be
+/// removed from layered TOML files.
+pub fn save_edits(
+    path: &Path,
+    overlay: bool,
+    original: &SerializedManifest,
+    edited: Value,
+) -> Result<String, Error> {
+    serde_json::from_value::<Manifest>(edited.clone()).context("invalid manifest")?;
+
+    let original: Value =
+        serde_json::from_str(original.get()).context("failed to parse original manifest")?;
+
+    let format = Format::from_path(path);
+    let mut file = match std::fs::read_to_string(path) {
+        Ok(source) => format.parse(&source)?,
+        // Overlays of profiles are created when they are first edited
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => {
+            Value::Object(Default::default())
+        }
+        Err(error) => return Err(error).context("failed to read manifest file"),
+    };
+
+    let changed = apply_edits(&mut file, overlay, &original, &edited)?;
+    if changed.is_empty() {
+        return Ok(format!("No changes to save to {}", path.display()));
+    }
+
+    if format == Format::Toml {
+        let removed = changed
+            .iter()
+            .filter(|field| file.get(**field).is_some_and(Value::is_null))
+            .copied()
+            .collect::<Vec<_>>();
+
+        if !removed.is_empty() {
+            return Err(anyhow!(
+                "cannot remove {} in {}, since TOML has no null to override the base manifest. \
+                 Remove the fields from the base manifest, or use a JSON or YAML file instead",
+                removed.join(", "),
+                path.display()
+            ));
+        }
+    }
+
+    std::fs::write(path, format.serialize(&file)?)
+        .with_context(|| format!("failed to write {}", path.display()))?;
+
+    Ok(format!(
+        "Saved changes to {} in {}",
+        changed.join(", "),
+        path.display()
+    ))
+}
+
+/// Apply the changes between the original and the edited manifest to a manifest file
+///
+/// Fields that are removed are set to `null` if the file is layered on top of another manifest,
+/// since they would otherwise be inherited from it. Returns the fields that were changed.
+fn apply_edits(
+    file: &mut Value,
+    overlay: bool,
+    original: &Value,
+    edited: &Value,
+) -> Result<Vec<&'static str>, Error> {
+    let file = file
+        .as_object_mut()
+        .ok_or_else(|| anyhow!("manifest file must contain an object"))?;
+
+    let layered = overlay || file.contains_key("extends");
+
+    let mut changed = Vec::new();
+    for field in EDITABLE_FIELDS {
+        let edited = edited.get(field);
+        if original.get(field) == edited {
+            continue;
+        }
+
+        match edited {
+            Some(value) => file.insert(field.to_string(), value.clone()),
+            None if layered => file.insert(field.to_string(), Value::Null),
+            None => file.remove(field),
+        };
+        changed.push(field);
+    }
+
+    Ok(changed)
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    use super::*;
+
+    #[test]
+    fn apply_edits_only_writes_changed_fields() {
+        let mut file = json!({
+            "name": "octoapp-{{user}}",
+            "url": "https://example.com",
+            "default_events": ["issues"]
+        });
+        let original = json!({
+            "name": "octoapp-otter",
+            "url": "https://example.com",
+            "redirect_url": "http://127.0.0.1:3000/callback",
+            "default_events": ["issues"]
+        });
+        let edited = json!({
+            "name": "octoapp-otter",
+            "url": "https://example.com",
+            "redirect_url": "http://127.0.0.1:3000/callback",
+            "default_permissions": {"issues": "read"}
+        });
+
+        let changed = apply_edits(&mut file, false, &original, &edited).unwrap();
+
+        assert_eq!(vec!["default_permissions", "default_events"], changed);
+        assert_eq!(
+            json!({
+                "name": "octoapp-{{user}}",
+                "url": "https://example.com",
+                "default_permissions": {"issues": "read"}
+            }),
+            file
+        );
+    }
+
+    #[test]
+    fn apply_edits_sets_removed_fields_to_null_in_layered_files() {
+        let mut file = json!({"extends": "base.json"});
+        let original = json!({"url": "https://example.com", "default_events": ["issues"]});
+        let edited = json!({"url": "https://example.com"});
+
+        apply_edits(&mut file, false, &original, &edited).unwrap();
+
+        assert_eq!(
+            json!({"extends": "base.json", "default_events": null}),
+            file
+        );
+    }
+
+    #[test]
+    fn save_edits_writes_file_in_its_format() {
+        let directory = TempDir::new().unwrap();
+        let path = directory.path().join("manifest.yml");
+        std::fs::write(&path, "url: https://example.com\n").unwrap();
+        let original = SerializedManifest::new(r#"{"url":"https://example.com"}"#);
+
+        let message = save_edits(
+            &path,
+            false,
+            &original,
+            json!({"url": "https://example.com", "public": true}),
+        )
+        .unwrap();
+
+        assert!(message.starts_with("Saved changes to public in"));
+        assert_eq!(
+            json!({"url": "https://example.com", "public": true}),
+            Format::Yaml
+                .parse(&std::fs::read_to_string(&path).unwrap())
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn save_edits_creates_missing_overlay() {
+        let directory = TempDir::new().unwrap();
+        let path = directory.path().join("manifest.dev.json");
+        let original = SerializedManifest::new(r#"{"url":"https://example.com"}"#);
+
+        save_edits(
+            &path,
+            true,
+            &original,
+            json!({"url": "https://example.com", "name": "octoapp-dev"}),
+        )
+        .unwrap();
+
+        assert_eq!(
+            json!({"name": "octoapp-dev"}),
+            Format::Json
+                .parse(&std::fs::read_to_string(&path).unwrap())
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn save_edits_rejects_removing_fields_from_toml_overlay() {
+        let directory = TempDir::new().unwrap();
+        let path = directory.path().join("manifest.dev.toml");
+        std::fs::write(&path, "name = \"octoapp-dev\"\n").unwrap();
+        let original = SerializedManifest::new(
+            r#"{"name":"octoapp-dev","url":"https://example.com","public":true}"#,
+        );
+
+        let error = save_edits(
+            &path,
+            true,
+            &original,
+            json!({"name": "octoapp-dev", "url": "https://example.com"}),
+        )
+        .unwrap_err();
+
+        assert!(error.to_string().starts_with("cannot remove public in"));
+        assert_eq!(
+            "name = \"octoapp-dev\"\n",
+            std::fs::read_to_string(&path).unwrap()
+        );
+    }
+
+    #[test]
+    fn save_edits_writes_toml_overlay() {
+        let directory = TempDir::new().unwrap();
+        let path = directory.path().join("manifest.dev.toml");
+        let original = SerializedManifest::new(r#"{"url":"https://example.com"}"#);
+
+        save_edits(
+            &path,
+            true,
+            &original,
+            json!({"url": "https://example.com", "public": true}),
+        )
+        .unwrap();
+
+        assert_eq!(
+            json!({"public": true}),
+            Format::Toml
+                .parse(&std::fs::read_to_string(&path).unwrap())
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn save_edits_rejects_invalid_manifest() {
+        let directory = TempDir::new().unwrap();
+        let path = directory.path().join("manifest.json");
+        let original = SerializedManifest::new(r#"{"url":"https://example.com"}"#);
+
+        let error = save_edits(&path, false, &original, json!({"name": "octoapp"})).unwrap_err();
+
+        assert_eq!("invalid manifest", error.to_string());
+        assert!(!path.exists());
+    }
+}
@@ -1,20 +1,40 @@
 //! Web server to accept the temporary code from GitHub
 
+use std::io::ErrorKind;
 use std::net::SocketAddr;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 use anyhow::{Context, Error};
-use axum::extract::{Query, State};
-use axum::response::IntoResponse;
-use axum::routing::get;
-use axum::Router;
+use axum::extract::{Query, Request, State};
+use axum::http::header::{
+    CONTENT_SECURITY_POLICY, REFERRER_POLICY, X_CONTENT_TYPE_OPTIONS, X_FRAME_OPTIONS,
+};
+use axum::http::{HeaderValue, StatusCode};
+use axum::middleware::{from_fn, map_response_with_state, Next};
+use axum::response::{Html, IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
 use serde::Deserialize;
+use serde_json::Value;
 use tokio::net::TcpListener;
 use tokio::sync::mpsc::{channel, Receiver, Sender};
+use tokio::sync::oneshot;
+use tokio::task::JoinHandle;
+use tracing::{debug, trace};
 use url::Url;
 
-use crate::manifest::{Manifest, SerializedManifest};
+use crate::error::CliError;
+use crate::manifest::{
+    profile_path, HookAttributes, Manifest, ManifestOverrides, SerializedManifest, Variables,
+};
+use crate::register::apps::Target;
+use crate::register::assets::{form_css, form_js};
+use crate::register::conversion::RegisterResult;
 use crate::register::form::Form;
+use crate::register::pages::{ErrorPage, SuccessPage};
+use crate::register::review::save_edits;
+use crate::register::templates::Templates;
+use crate::register::RegisterArgs;
 
 /// Query parameters for the temporary code callback
 ///
@@ -27,108 +47,451 @@ struct Params {
     code: String,
 }
 
+/// A temporary code that was received from GitHub
+///
+/// The code is exchanged for the app's secrets outside of the web server. The outcome of the
+/// exchange is sent back to the web server, which shows it to the user in the browser.
+#[derive(Debug)]
+pub struct Registration {
+    /// The index of the app that was registered
+    pub app: usize,
+
+    /// The temporary code returned by GitHub
+    pub code: String,
+
+    /// The channel to which to send the registered app, or the reason why the registration failed
+    pub outcome: oneshot::Sender<Result<RegisterResult, String>>,
+}
+
+/// The web server that runs in the background
+///
+/// The web server is shut down gracefully, so that the page that concludes the registration process
+/// is delivered to the browser before the command exits.
+#[derive(Debug)]
+pub struct WebServer {
+    /// The address on which the web server listens
+    addr: SocketAddr,
+
+    /// The paths under which the pages of the apps are served
+    paths: Vec<String>,
+
+    /// The channel on which the temporary codes are received
+    receiver: Receiver<Registration>,
+
+    /// The channel that signals the web server to shut down
+    shutdown: oneshot::Sender<()>,
+
+    /// The task in which the web server runs
+    task: JoinHandle<Result<(), Error>>,
+}
+
+impl WebServer {
+    /// Return the address on which the web server listens
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// Return the URL of the form that registers the app with the given index
+    pub fn url(&self, app: usize) -> String {
+        format!("http://{}{}/", self.addr(), self.paths[app])
+    }
+
+    /// Wait for the next temporary code from GitHub
+    pub async fn next_registration(&mut self) -> Option<Registration> {
+        self.receiver.recv().await
+    }
+
+    /// Shut down the web server after all open requests have been answered
+    ///
+    /// Temporary codes that have not been processed are dropped, which shows an error page to the
+    /// user instead of keeping the request open.
+    pub async fn shutdown(self) -> Result<(), Error> {
+        let Self {
+            receiver,
+            shutdown,
+            task,
+            ..
+        } = self;
+        drop(receiver);
+
+        // The server has already stopped if the signal cannot be sent
+        let _ = shutdown.send(());
+
+        task.await.context("failed to shut down web server")?
+    }
+}
+
 /// Application state for the axum server
 ///
-/// The state for the axum server includes a channel to send the temporary code to, and the manifest
-/// that was generated with the local address.
+/// Each app has its own state, which includes a channel to send the temporary code to, the form
+/// that renders the manifest that was generated with the local address, and the file to which
+/// edits on the form can be saved.
 #[derive(Clone, Debug)]
 struct AppState {
+    /// The index of the app
+    app: usize,
+
+    /// The path under which the pages of the app are served, which is empty for a single app
+    path: String,
+
     /// The channel to which to send the temporary code
-    channel: Sender<String>,
+    channel: Sender<Registration>,
+
+    /// The templates for the pages of the registration process
+    templates: Templates,
 
-    /// The endpoint of the GitHub API
-    github: Url,
+    /// The form to review the manifest
+    form: Form,
 
     /// The manifest to register the GitHub App
     manifest: SerializedManifest,
+
+    /// The file to which edits on the form are saved, and whether it is the overlay of a profile
+    manifest_file: Option<(PathBuf, bool)>,
 }
 
 /// Start a web server on a background thread
 ///
 /// This function starts a web server that accepts the temporary code from GitHub when a new GitHub
 /// App is registered. The server listens on a random port and sends the temporary code to a channel
-/// for further processing.
+/// for further processing. The pages of the server are rendered with the given templates.
+///
+/// A single app is served at the root of the server. When several apps are registered, each app is
+/// served under its own path, e.g. `/apps/bot/` with the callback `/apps/bot/callback`.
 ///
 /// The server runs in a background task as to not block the main thread.
 pub async fn start_background_web_server(
-    manifest_path: &Path,
-    github: Url,
-    port: Option<u16>,
-) -> Result<(SocketAddr, Receiver<String>), Error> {
+    args: &RegisterArgs,
+    targets: &[Target],
+    templates: Templates,
+) -> Result<WebServer, Error> {
+    let port = args.port();
+
     // Either use the given port or let the OS choose a random port
     let interface = format!("127.0.0.1:{}", port.unwrap_or(0));
-    let listener = TcpListener::bind(interface.parse::<SocketAddr>().unwrap()).await?;
+    let listener = match TcpListener::bind(interface.parse::<SocketAddr>().unwrap()).await {
+        Ok(listener) => listener,
+        Err(error) if error.kind() == ErrorKind::AddrInUse => {
+            return Err(error).context(CliError::PortInUse {
+                port: port.unwrap_or_default(),
+            });
+        }
+        Err(error) => return Err(error).context("failed to start web server"),
+    };
     let addr = listener.local_addr()?;
+    debug!(%addr, "started web server");
 
-    let manifest = generate_and_serialize_manifest(manifest_path, &addr)?;
     let (sender, receiver) = channel(1);
+    let several = targets.len() > 1;
+
+    let mut states = Vec::with_capacity(targets.len());
+    for (index, target) in targets.iter().enumerate() {
+        let path = match several {
+            true => format!("/apps/{}", target.name()),
+            false => String::new(),
+        };
+
+        let state = app_state(args, target, &addr, index, path, sender.clone(), &templates);
 
-    let _server = tokio::spawn(run_axum_server(sender, listener, github, manifest));
+        states.push(match several {
+            true => {
+                state.with_context(|| format!("failed to prepare the app `{}`", target.name()))?
+            }
+            false => state?,
+        });
+    }
 
-    Ok((addr, receiver))
+    let content_security_policy = content_security_policy(states[0].form.form_endpoint())?;
+    let paths = states.iter().map(|state| state.path.clone()).collect();
+
+    let (shutdown, shutdown_signal) = oneshot::channel();
+    let task = tokio::spawn(run_axum_server(
+        listener,
+        states,
+        content_security_policy,
+        shutdown_signal,
+    ));
+
+    Ok(WebServer {
+        addr,
+        paths,
+        receiver,
+        shutdown,
+        task,
+    })
+}
+
+/// Generate the manifest of an app and prepare the state of its pages
+fn app_state(
+    args: &RegisterArgs,
+    target: &Target,
+    addr: &SocketAddr,
+    index: usize,
+    path: String,
+    channel: Sender<Registration>,
+    templates: &Templates,
+) -> Result<AppState, Error> {
+    let manifest_path = target.manifest().as_deref();
+    let profile = target.profile().as_deref();
+
+    let manifest = generate_and_serialize_manifest(
+        manifest_path,
+        profile,
+        args.overrides(),
+        addr,
+        &path,
+        args.tunnel().as_ref(),
+    )?;
+    trace!(
+        app = target.name(),
+        manifest = manifest.get(),
+        "generated manifest"
+    );
+
+    // Edits are saved to the overlay of the profile, and never to `Cargo.toml`
+    let manifest_file = manifest_path.map(|path| match profile {
+        Some(profile) => (profile_path(path, profile), true),
+        None => (path.to_path_buf(), false),
+    });
+
+    let form = Form::new(
+        args.github().clone(),
+        target.org().as_deref(),
+        &serde_json::from_str(manifest.get()).context("failed to parse generated manifest")?,
+        manifest_file.is_some(),
+        templates.project().clone(),
+    );
+
+    Ok(AppState {
+        app: index,
+        path,
+        channel,
+        templates: templates.clone(),
+        form,
+        manifest,
+        manifest_file,
+    })
 }
 
 /// Generate the manifest
 ///
 /// The manifest is generated by reading the manifest file that is passed to the CLI as an
-/// argument, and then overwriting the `redirect_url` with the address of the local web server.
+/// argument, or the manifest in `Cargo.toml`, merging the overlay of the profile, applying the
+/// overrides from the command line, and then overwriting the `redirect_url` with the callback of
+/// the app on the local web server. The port of the server is available to the manifest as the
+/// `{{port}}` placeholder. If a tunnel is used, its channel replaces the URL of the webhook.
 fn generate_and_serialize_manifest(
-    manifest_path: &Path,
+    manifest_path: Option<&Path>,
+    profile: Option<&str>,
+    overrides: &ManifestOverrides,
     addr: &SocketAddr,
+    path: &str,
+    tunnel: Option<&Url>,
 ) -> Result<SerializedManifest, Error> {
-    let mut manifest = Manifest::from_file(manifest_path)?;
+    let variables = Variables::builder().port(addr.port()).build();
+    let mut manifest = Manifest::load(manifest_path, profile, &variables)?;
+    overrides.apply(&mut manifest);
+
+    let unknown_fields = manifest.unknown_field_names();
+    if !unknown_fields.is_empty() {
+        eprintln!(
+            "Warning: the manifest contains fields that are unknown to github-dev-app and sent to \
+             GitHub unchanged: {}",
+            unknown_fields.join(", ")
+        );
+    }
 
-    let redirect_url = format!("http://{}:{}/callback", addr.ip(), addr.port()).into();
+    let redirect_url = format!("http://{}:{}{}/callback", addr.ip(), addr.port(), path).into();
     manifest.set_redirect_url(Some(redirect_url));
 
+    // The existing webhook is changed in place, so that its other fields are kept
+    if let Some(tunnel) = tunnel {
+        let hook_attributes = match manifest.hook_attributes().clone() {
+            Some(mut hook_attributes) => {
+                hook_attributes.set_url(tunnel.as_str().into());
+                hook_attributes
+            }
+            None => HookAttributes::builder().url(tunnel.as_str()).build(),
+        };
+
+        manifest.set_hook_attributes(Some(hook_attributes));
+    }
+
     manifest.try_into()
 }
 
 /// Start the axum server to accept the temporary code
 ///
-/// This function starts an axum server that listens for a POST request on the root path. The server
-/// will accept the temporary code from the query parameters and send it to a channel for further
-/// processing.
+/// This function starts an axum server that renders the form of each app on its path and saves
+/// edits on the form to the app's manifest file. The server will accept the temporary code from the
+/// query parameters of the app's callback and send it to a channel for further processing.
 ///
-/// Spawn this function in a tokio task to start the server in the background.
+/// Spawn this function in a tokio task to start the server in the background. The server shuts down
+/// gracefully when the shutdown signal is received.
 async fn run_axum_server(
-    channel: Sender<String>,
     listener: TcpListener,
-    github: Url,
-    manifest: SerializedManifest,
+    states: Vec<AppState>,
+    content_security_policy: HeaderValue,
+    shutdown_signal: oneshot::Receiver<()>,
 ) -> Result<(), Error> {
-    let app = Router::new()
-        .route("/", get(show_form))
-        .route("/callback", get(accept_temporary_code))
-        .with_state(AppState {
-            channel,
-            github,
-            manifest,
-        });
+    let app = states.into_iter().fold(
+        Router::new()
+            .route("/assets/form.css", get(form_css))
+            .route("/assets/form.js", get(form_js)),
+        |app, state| {
+            let path = state.path.clone();
+
+            app.merge(
+                Router::new()
+                    .route(&format!("{path}/"), get(show_form))
+                    .route(&format!("{path}/manifest"), post(save_manifest))
+                    .route(&format!("{path}/callback"), get(accept_temporary_code))
+                    .with_state(state),
+            )
+        },
+    );
+
+    let app = app
+        .layer(map_response_with_state(
+            content_security_policy,
+            set_security_headers,
+        ))
+        .layer(from_fn(log_requests));
 
     axum::serve(listener, app)
+        .with_graceful_shutdown(async {
+            let _ = shutdown_signal.await;
+        })
         .await
         .context("failed to start axum server")?;
 
     Ok(())
 }
 
+/// Build the Content-Security-Policy for the local web server
+///
+/// The pages of the server may only load the stylesheet and script that are embedded into the
+/// binary, may only connect to the server itself, and may only submit forms to GitHub.
+fn content_security_policy(form_endpoint: &Url) -> Result<HeaderValue, Error> {
+    let policy = format!(
+        "default-src 'none'; script-src 'self'; style-src 'self'; img-src 'self'; \
+         connect-src 'self'; form-action {}; base-uri 'none'; frame-ancestors 'none'",
+        form_endpoint.origin().ascii_serialization()
+    );
+
+    HeaderValue::from_str(&policy).context("failed to build Content-Security-Policy")
+}
+
+/// Add security headers to every response of the server
+///
+/// Besides the Content-Security-Policy, the headers prevent the pages from being embedded into
+/// other websites and from leaking the local address in the `Referer` header.
+async fn set_security_headers(
+    State(content_security_policy): State<HeaderValue>,
+    mut response: Response,
+) -> Response {
+    let headers = response.headers_mut();
+
+    headers.insert(CONTENT_SECURITY_POLICY, content_security_policy);
+    headers.insert(X_CONTENT_TYPE_OPTIONS, HeaderValue::from_static("nosniff"));
+    headers.insert(X_FRAME_OPTIONS, HeaderValue::from_static("DENY"));
+    headers.insert(REFERRER_POLICY, HeaderValue::from_static("no-referrer"));
+
+    response
+}
+
+/// Log the requests to the server and the status of their responses
+///
+/// Only the path of a request is logged, since the query of the callback contains the temporary
+/// code that can be exchanged for the app's secrets.
+async fn log_requests(request: Request, next: Next) -> Response {
+    let method = request.method().clone();
+    let path = request.uri().path().to_string();
+
+    let response = next.run(request).await;
+    debug!(%method, %path, status = %response.status(), "handled request");
+
+    response
+}
+
 /// Render the form that starts the registration process
 ///
 /// This function renders the form that is used to start the registration process for a new GitHub
 /// App. The form includes the manifest that was generated from the manifest file.
-async fn show_form(state: State<AppState>) -> impl IntoResponse {
-    Form::new(state.github.clone(), state.manifest.clone())
+async fn show_form(state: State<AppState>) -> Response {
+    render_page(&state.templates, "form.html", &state.form)
+}
+
+/// Save the edits on the form to the manifest file
+///
+/// The form posts the edited manifest as JSON, which browsers do not allow other websites to do
+/// without a preflight request. Only the fields that were changed are written to the file.
+async fn save_manifest(state: State<AppState>, Json(edited): Json<Value>) -> impl IntoResponse {
+    let Some((path, overlay)) = &state.manifest_file else {
+        return (
+            StatusCode::CONFLICT,
+            "The manifest was read from Cargo.toml and cannot be saved".to_string(),
+        );
+    };
+
+    match save_edits(path, *overlay, &state.manifest, edited) {
+        Ok(message) => (StatusCode::OK, message),
+        Err(error) => (StatusCode::UNPROCESSABLE_ENTITY, format!("{error:#}")),
+    }
 }
 
 /// Handle the callback after registering a new GitHub App
 ///
 /// After registering a new GitHub App from a manifest, GitHub will redirect the user back to a
 /// callback URL. The redirect includes a temporary code that can be exchanged for the app's private
-/// key and secrets. This function accepts the temporary code and sends it to a channel for further
-/// processing.
-async fn accept_temporary_code(state: State<AppState>, query: Query<Params>) {
-    state.channel.send(query.code.clone()).await.unwrap()
+/// key and secrets. This function sends the temporary code to a channel for further processing, and
+/// waits for the outcome to show the user whether the app was created.
+async fn accept_temporary_code(state: State<AppState>, query: Query<Params>) -> Response {
+    debug!(app = state.app, "received temporary code from GitHub");
+
+    let (outcome, receiver) = oneshot::channel();
+    let registration = Registration {
+        app: state.app,
+        code: query.code.clone(),
+        outcome,
+    };
+
+    let outcome = match state.channel.send(registration).await {
+        Ok(()) => receiver
+            .await
+            .unwrap_or_else(|_| Err("the registration was aborted".into())),
+        Err(_) => Err("the registration is no longer in progress".into()),
+    };
+
+    let project = state.templates.project().clone();
+    match outcome {
+        Ok(result) => {
+            let page = SuccessPage {
+                app_name: result.name,
+                installation_url: result.installation_url,
+                env_file: result.env_file.display().to_string(),
+                project,
+            };
+
+            render_page(&state.templates, "success.html", &page)
+        }
+        Err(message) => {
+            let page = ErrorPage { message, project };
+
+            render_page(&state.templates, "error.html", &page)
+        }
+    }
+}
+
+/// Render a page with the project's template or the built-in template
+fn render_page<T>(templates: &Templates, name: &str, page: &T) -> Response
+where
+    T: askama_axum::Template + serde::Serialize,
+{
+    match templates.render(name, page) {
+        Ok(html) => Html(html).into_response(),
+        Err(error) => (StatusCode::INTERNAL_SERVER_ERROR, format!("{error:#}")).into_response(),
+    }
 }
 
 #[cfg(test)]
@@ -136,13 +499,38 @@ mod tests {
     use std::io::Write;
     use std::str::FromStr;
 
+    use axum::http::header::CONTENT_TYPE;
     use indoc::indoc;
     use pretty_assertions::assert_eq;
     use reqwest::Client;
     use tempfile::NamedTempFile;
 
+    use crate::register::apps::resolve_targets;
+
     use super::*;
 
+    async fn start_server(manifest: &NamedTempFile) -> WebServer {
+        start_server_for(&[manifest]).await
+    }
+
+    async fn start_server_for(manifests: &[&NamedTempFile]) -> WebServer {
+        let args = RegisterArgs::builder()
+            .manifest(
+                manifests
+                    .iter()
+                    .map(|manifest| manifest.path().to_path_buf())
+                    .collect::<Vec<_>>(),
+            )
+            .github(Url::parse("https://api.github.com").unwrap())
+            .port(None)
+            .build();
+        let targets = resolve_targets(&args).unwrap();
+
+        start_background_web_server(&args, &targets, Templates::default())
+            .await
+            .unwrap()
+    }
+
     #[test]
     fn generate_manifest_overwrites_redirect_url() {
         let manifest = indoc! {r#"
@@ -156,7 +544,15 @@ mod tests {
 
         let addr = SocketAddr::from_str("127.0.0.1:8080").unwrap();
 
-        let serialized_manifest = generate_and_serialize_manifest(file.path(), &addr).unwrap();
+        let serialized_manifest = generate_and_serialize_manifest(
+            Some(file.path()),
+            None,
+            &ManifestOverrides::default(),
+            &addr,
+            "",
+            None,
+        )
+        .unwrap();
         let manifest: Manifest = serde_json::from_str(serialized_manifest.get()).unwrap();
 
         assert_eq!(
@@ -165,12 +561,58 @@ mod tests {
         );
     }
 
+    #[test]
+    fn generate_manifest_sets_tunnel_as_webhook_url() {
+        let manifest = indoc! {r#"
+            {
+                "url": "https://www.example.com",
+                "hook_attributes": {
+                    "url": "https://example.com/github/events",
+                    "active": false,
+                    "secret": "otters"
+                }
+            }
+        "#};
+        let mut file = NamedTempFile::new().unwrap();
+        file.write_all(manifest.as_bytes()).unwrap();
+
+        let addr = SocketAddr::from_str("127.0.0.1:8080").unwrap();
+        let tunnel = Url::parse("https://smee.io/otters").unwrap();
+
+        let serialized_manifest = generate_and_serialize_manifest(
+            Some(file.path()),
+            None,
+            &ManifestOverrides::default(),
+            &addr,
+            "",
+            Some(&tunnel),
+        )
+        .unwrap();
+        let manifest: Manifest = serde_json::from_str(serialized_manifest.get()).unwrap();
+        let hook_attributes = manifest.hook_attributes().clone().unwrap();
+
+        assert_eq!("https://smee.io/otters", hook_attributes.url().get());
+        assert_eq!(&Some(false), hook_attributes.active());
+        assert_eq!(
+            Some(&serde_json::json!("otters")),
+            hook_attributes.unknown_fields().get("secret")
+        );
+    }
+
     #[test]
     fn generate_manifest_errors_on_missing_file() {
         let file = Path::new("missing-file.json");
         let addr = SocketAddr::from_str("127.0.0.1:8080").unwrap();
 
-        let error = generate_and_serialize_manifest(file, &addr).unwrap_err();
+        let error = generate_and_serialize_manifest(
+            Some(file),
+            None,
+            &ManifestOverrides::default(),
+            &addr,
+            "",
+            None,
+        )
+        .unwrap_err();
 
         assert_eq!("failed to read manifest file", error.to_string());
     }
@@ -185,13 +627,8 @@ mod tests {
         let mut file = NamedTempFile::new().unwrap();
         file.write_all(manifest.as_bytes()).unwrap();
 
-        let (addr, _receiver) = start_background_web_server(
-            file.path(),
-            Url::parse("https://api.github.com").unwrap(),
-            None,
-        )
-        .await
-        .unwrap();
+        let server = start_server(&file).await;
+        let addr = server.addr();
         let callback_url = format!("http://{}/callback", addr);
 
         let body = Client::new()
@@ -207,7 +644,7 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn accept_temporary_code_sends_code_to_channel() {
+    async fn show_form_loads_embedded_assets() {
         let manifest = indoc! {r#"
             {
                 "url": "https://www.example.com"
@@ -216,23 +653,248 @@ mod tests {
         let mut file = NamedTempFile::new().unwrap();
         file.write_all(manifest.as_bytes()).unwrap();
 
-        let (addr, mut receiver) = start_background_web_server(
-            file.path(),
-            Url::parse("https://api.github.com").unwrap(),
-            None,
-        )
-        .await
-        .unwrap();
+        let server = start_server(&file).await;
+        let addr = server.addr();
+
+        let body = Client::new()
+            .get(format!("http://{}/", addr))
+            .send()
+            .await
+            .expect("failed to execute GET / request")
+            .text()
+            .await
+            .unwrap();
+
+        assert!(!body.contains("cdn.tailwindcss.com"));
+
+        for (path, content_type) in [
+            ("/assets/form.css", "text/css; charset=utf-8"),
+            ("/assets/form.js", "text/javascript; charset=utf-8"),
+        ] {
+            assert!(body.contains(path));
+
+            let response = Client::new()
+                .get(format!("http://{}{}", addr, path))
+                .send()
+                .await
+                .expect("failed to execute GET request for asset");
+
+            assert_eq!(StatusCode::OK, response.status());
+            assert_eq!(content_type, response.headers()[CONTENT_TYPE]);
+        }
+    }
+
+    #[tokio::test]
+    async fn responses_have_content_security_policy() {
+        let manifest = indoc! {r#"
+            {
+                "url": "https://www.example.com"
+            }
+        "#};
+        let mut file = NamedTempFile::new().unwrap();
+        file.write_all(manifest.as_bytes()).unwrap();
+
+        let server = start_server(&file).await;
+        let addr = server.addr();
+
+        for path in ["/", "/assets/form.js", "/missing"] {
+            let response = Client::new()
+                .get(format!("http://{}{}", addr, path))
+                .send()
+                .await
+                .expect("failed to execute GET request");
+
+            assert_eq!(
+                "default-src 'none'; script-src 'self'; style-src 'self'; img-src 'self'; \
+                 connect-src 'self'; form-action https://github.com; base-uri 'none'; \
+                 frame-ancestors 'none'",
+                response.headers()[CONTENT_SECURITY_POLICY]
+            );
+            assert_eq!("nosniff", response.headers()[X_CONTENT_TYPE_OPTIONS]);
+        }
+    }
+
+    #[tokio::test]
+    async fn save_manifest_writes_edits_to_file() {
+        let manifest = indoc! {r#"
+            {
+                "name": "octoapp",
+                "url": "https://www.example.com"
+            }
+        "#};
+        let mut file = NamedTempFile::new().unwrap();
+        file.write_all(manifest.as_bytes()).unwrap();
+
+        let server = start_server(&file).await;
+        let addr = server.addr();
+
+        let response = Client::new()
+            .post(format!("http://{}/manifest", addr))
+            .json(&serde_json::json!({
+                "name": "octoapp",
+                "url": "https://www.example.com",
+                "redirect_url": format!("http://{}/callback", addr),
+                "public": true
+            }))
+            .send()
+            .await
+            .expect("failed to execute POST /manifest request");
+
+        assert_eq!(StatusCode::OK, response.status());
+        assert_eq!(
+            serde_json::json!({
+                "name": "octoapp",
+                "url": "https://www.example.com",
+                "public": true
+            }),
+            serde_json::from_str::<Value>(&std::fs::read_to_string(file.path()).unwrap()).unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn save_manifest_rejects_invalid_manifest() {
+        let manifest = indoc! {r#"
+            {
+                "url": "https://www.example.com"
+            }
+        "#};
+        let mut file = NamedTempFile::new().unwrap();
+        file.write_all(manifest.as_bytes()).unwrap();
+
+        let server = start_server(&file).await;
+        let addr = server.addr();
+
+        let response = Client::new()
+            .post(format!("http://{}/manifest", addr))
+            .json(&serde_json::json!({"name": "octoapp"}))
+            .send()
+            .await
+            .expect("failed to execute POST /manifest request");
+
+        assert_eq!(StatusCode::UNPROCESSABLE_ENTITY, response.status());
+        assert_eq!(manifest, std::fs::read_to_string(file.path()).unwrap());
+    }
+
+    #[tokio::test]
+    async fn accept_temporary_code_shows_registered_app() {
+        let mut file = NamedTempFile::new().unwrap();
+        file.write_all(br#"{"url": "https://www.example.com"}"#)
+            .unwrap();
+
+        let mut server = start_server(&file).await;
+        let callback = format!(
+            "http://{}/callback?code=otters-are-the-cutest",
+            server.addr()
+        );
+        let response = tokio::spawn(async move { Client::new().get(callback).send().await });
+
+        let registration = server.next_registration().await.unwrap();
+        assert_eq!("otters-are-the-cutest", registration.code);
+
+        let result = RegisterResult {
+            id: 1,
+            name: "octoapp-dev".into(),
+            slug: Some("octoapp-dev".into()),
+            owner: Some("otterbuild".into()),
+            html_url: Some("https://github.com/apps/octoapp-dev".into()),
+            installation_url:
+                "https://github.com/organizations/otterbuild/settings/apps/octoapp-dev/installations"
+                    .into(),
+            env_file: "backend/.env".into(),
+            prefix: "GITHUB_".into(),
+        };
+        registration.outcome.send(Ok(result)).unwrap();
+
+        let body = response.await.unwrap().unwrap().text().await.unwrap();
+        server.shutdown().await.unwrap();
+
+        assert!(body.contains("GitHub App created"));
+        assert!(body.contains(
+            "https://github.com/organizations/otterbuild/settings/apps/octoapp-dev/installations"
+        ));
+        assert!(body.contains("backend/.env"));
+    }
+
+    #[tokio::test]
+    async fn accept_temporary_code_shows_error() {
+        let mut file = NamedTempFile::new().unwrap();
+        file.write_all(br#"{"url": "https://www.example.com"}"#)
+            .unwrap();
+
+        let mut server = start_server(&file).await;
+        let callback = format!(
+            "http://{}/callback?code=otters-are-the-cutest",
+            server.addr()
+        );
+        let response = tokio::spawn(async move { Client::new().get(callback).send().await });
+
+        let registration = server.next_registration().await.unwrap();
+        registration
+            .outcome
+            .send(Err("failed to convert temporary code".into()))
+            .unwrap();
+
+        let body = response.await.unwrap().unwrap().text().await.unwrap();
+
+        assert!(body.contains("Failed to create GitHub App"));
+        assert!(body.contains("failed to convert temporary code"));
+    }
 
-        let _response = Client::new()
-            .get(format!(
-                "http://{}/callback?code=otters-are-the-cutest",
-                addr
-            ))
+    #[tokio::test]
+    async fn serves_each_app_under_its_own_path() {
+        let mut bot = NamedTempFile::with_prefix("bot").unwrap();
+        bot.write_all(br#"{"url": "https://www.example.com"}"#)
+            .unwrap();
+        let mut login = NamedTempFile::with_prefix("login").unwrap();
+        login
+            .write_all(br#"{"url": "https://www.example.com"}"#)
+            .unwrap();
+
+        let mut server = start_server_for(&[&bot, &login]).await;
+        let login_url = server.url(1);
+        let login_name = login.path().file_stem().unwrap().to_str().unwrap();
+
+        assert_eq!(
+            format!("http://{}/apps/{}/", server.addr(), login_name),
+            login_url
+        );
+
+        let body = Client::new()
+            .get(&login_url)
             .send()
             .await
-            .expect("failed to execute GET /callback request");
+            .expect("failed to execute GET request for form")
+            .text()
+            .await
+            .unwrap();
+        assert!(body.contains(&format!("{login_url}callback")));
 
-        assert_eq!(Some("otters-are-the-cutest".into()), receiver.recv().await);
+        let callback = format!("{login_url}callback?code=otters-are-the-cutest");
+        let response = tokio::spawn(async move { Client::new().get(callback).send().await });
+
+        let registration = server.next_registration().await.unwrap();
+        assert_eq!(1, registration.app);
+
+        drop(registration);
+        response.await.unwrap().unwrap();
+        server.shutdown().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn shutdown_stops_server() {
+        let mut file = NamedTempFile::new().unwrap();
+        file.write_all(br#"{"url": "https://www.example.com"}"#)
+            .unwrap();
+
+        let server = start_server(&file).await;
+        let addr = server.addr();
+
+        server.shutdown().await.unwrap();
+
+        assert!(Client::new()
+            .get(format!("http://{}/", addr))
+            .send()
+            .await
+            .is_err());
     }
 }
@@ -0,0 +1,209 @@
+//! Templates for the pages of the registration process
+//!
+//! The pages are rendered with the built-in askama templates, unless the project provides its own
+//! templates in a directory such as `.github-dev-app/templates`. The directory can contain the
+//! templates `form.html`, `success.html`, and `error.html`, which are rendered with minijinja, and
+//! a `context.toml` file with information about the project that is passed to all templates.
+
+use std::path::Path;
+use std::sync::Arc;
+
+use anyhow::{Context, Error};
+use askama_axum::Template;
+use getset::Getters;
+use minijinja::Environment;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+/// The names of the templates that can be overridden by a project
+const TEMPLATE_NAMES: [&str; 3] = ["form.html", "success.html", "error.html"];
+
+/// The name of the file with the project's context in the template directory
+const CONTEXT_FILE: &str = "context.toml";
+
+/// Information about the project that is passed to the templates
+///
+/// The context helps to explain the app-specific onboarding on the pages of the registration
+/// process. Any additional fields in `context.toml` are passed to custom templates as well.
+#[derive(Clone, Eq, PartialEq, Debug, Default, Getters, Deserialize, Serialize)]
+pub struct ProjectContext {
+    /// The name of the project
+    #[getset(get = "pub")]
+    name: Option<String>,
+
+    /// A link to the README of the project
+    #[getset(get = "pub")]
+    readme: Option<String>,
+
+    /// The steps that are required to set up the project after the app has been registered
+    #[serde(default)]
+    #[getset(get = "pub")]
+    setup_steps: Vec<String>,
+
+    /// Additional fields for custom templates
+    #[serde(flatten)]
+    extra: Map<String, Value>,
+}
+
+/// The templates for the pages of the registration process
+#[derive(Clone, Debug, Default)]
+pub struct Templates {
+    /// The custom templates of the project
+    environment: Arc<Environment<'static>>,
+
+    /// Information about the project that is passed to the templates
+    project: ProjectContext,
+}
+
+impl Templates {
+    /// Load the custom templates and the project's context from a directory
+    ///
+    /// Templates that don't exist in the directory fall back to the built-in templates. A missing
+    /// directory is not an error, so that projects only need to create it to customize the pages.
+    pub fn load(directory: &Path) -> Result<Self, Error> {
+        let mut environment = Environment::new();
+
+        for name in TEMPLATE_NAMES {
+            let path = directory.join(name);
+            if !path.is_file() {
+                continue;
+            }
+
+            let source = std::fs::read_to_string(&path)
+                .with_context(|| format!("failed to read template {}", path.display()))?;
+            environment
+                .add_template_owned(name, source)
+                .with_context(|| format!("failed to parse template {}", path.display()))?;
+        }
+
+        let path = directory.join(CONTEXT_FILE);
+        let project = if path.is_file() {
+            let source = std::fs::read_to_string(&path)
+                .with_context(|| format!("failed to read {}", path.display()))?;
+            toml::from_str(&source)
+                .with_context(|| format!("failed to parse {}", path.display()))?
+        } else {
+            ProjectContext::default()
+        };
+
+        Ok(Self {
+            environment: Arc::new(environment),
+            project,
+        })
+    }
+
+    /// Return the information about the project
+    pub fn project(&self) -> &ProjectContext {
+        &self.project
+    }
+
+    /// Render a page with the custom template of the project or the built-in template
+    ///
+    /// The fields of the page are the context of the custom template.
+    pub fn render<T>(&self, name: &str, page: &T) -> Result<String, Error>
+    where
+        T: Template + Serialize,
+    {
+        match self.environment.get_template(name) {
+            Ok(template) => template
+                .render(page)
+                .with_context(|| format!("failed to render template {name}")),
+            Err(_) => page
+                .render()
+                .with_context(|| format!("failed to render built-in template {name}")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use tempfile::TempDir;
+
+    use super::*;
+
+    #[derive(Template, Serialize)]
+    #[template(source = "built-in {{ greeting }}", ext = "txt")]
+    struct Page {
+        greeting: String,
+        project: ProjectContext,
+    }
+
+    fn page(project: &ProjectContext) -> Page {
+        Page {
+            greeting: "hello".into(),
+            project: project.clone(),
+        }
+    }
+
+    #[test]
+    fn load_falls_back_to_built_in_templates() {
+        let templates = Templates::load(Path::new("missing-directory")).unwrap();
+
+        assert_eq!(
+            "built-in hello",
+            templates
+                .render("form.html", &page(templates.project()))
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn render_uses_custom_template_with_project_context() {
+        let directory = TempDir::new().unwrap();
+        std::fs::write(
+            directory.path().join("form.html"),
+            "{{ project.name }}: {{ greeting }} {{ project.channel }}",
+        )
+        .unwrap();
+        std::fs::write(
+            directory.path().join("context.toml"),
+            "name = \"Octoapp\"\nchannel = \"<#octoapp>\"\n",
+        )
+        .unwrap();
+
+        let templates = Templates::load(directory.path()).unwrap();
+
+        assert_eq!(&Some("Octoapp".into()), templates.project().name());
+        assert_eq!(
+            "Octoapp: hello &lt;#octoapp&gt;",
+            templates
+                .render("form.html", &page(templates.project()))
+                .unwrap()
+        );
+        assert_eq!(
+            "built-in hello",
+            templates
+                .render("success.html", &page(templates.project()))
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn load_errors_on_invalid_template() {
+        let directory = TempDir::new().unwrap();
+        std::fs::write(directory.path().join("error.html"), "{% if %}").unwrap();
+
+        let error = Templates::load(directory.path()).unwrap_err();
+
+        assert!(error.to_string().starts_with("failed to parse template"));
+    }
+
+    #[test]
+    fn trait_send() {
+        fn assert_send<T: Send>() {}
+        assert_send::<Templates>();
+    }
+
+    #[test]
+    fn trait_sync() {
+        fn assert_sync<T: Sync>() {}
+        assert_sync::<Templates>();
+    }
+
+    #[test]
+    fn trait_unpin() {
+        fn assert_unpin<T: Unpin>() {}
+        assert_unpin::<Templates>();
+    }
+}
@@ -0,0 +1,25 @@
+//! Command-line arguments for the `tunnel` subcommand
+
+use clap::Parser;
+use getset::Getters;
+use typed_builder::TypedBuilder;
+use url::Url;
+
+/// Command-line arguments for the `tunnel` subcommand
+///
+/// The `tunnel` subcommand connects to a channel on smee.io, or any other server that implements
+/// the same protocol, and forwards the webhooks that are sent to the channel to a local URL.
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Parser, Getters, TypedBuilder)]
+pub struct TunnelArgs {
+    /// The URL of the channel that receives the webhooks, e.g. https://smee.io/abc123
+    #[arg(long)]
+    #[builder(setter(into))]
+    #[getset(get = "pub")]
+    source: Url,
+
+    /// The local URL to which the webhooks are forwarded, e.g. http://localhost:3000/webhooks
+    #[arg(long)]
+    #[builder(setter(into))]
+    #[getset(get = "pub")]
+    to: Url,
+}
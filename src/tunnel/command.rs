@@ -0,0 +1,245 @@
+//! Command to forward webhooks from a smee.io channel to a local server
+
+use std::time::Duration;
+
+use anyhow::{Context, Error};
+use async_trait::async_trait;
+use reqwest::header::ACCEPT;
+use reqwest::Client;
+use serde_json::json;
+use tokio::time::sleep;
+use url::Url;
+
+use crate::cli::Args;
+use crate::output::Output;
+use crate::Execute;
+
+use super::delivery::Delivery;
+use super::sse::EventStreamParser;
+use super::TunnelArgs;
+
+/// The time to wait before reconnecting to the channel after the connection was lost
+const RECONNECT_DELAY: Duration = Duration::from_secs(1);
+
+/// Forward webhooks from a smee.io channel to a local server
+///
+/// GitHub can only deliver webhooks to publicly reachable URLs. A smee.io channel provides such a
+/// URL and streams every webhook it receives to its subscribers as Server-Sent Events. This command
+/// subscribes to a channel and replays each webhook to a server running on the local machine.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct TunnelCommand<'a> {
+    /// The arguments for the command
+    args: &'a TunnelArgs,
+}
+
+impl<'a> TunnelCommand<'a> {
+    /// Create a new instance of the command
+    pub fn new(args: &'a TunnelArgs) -> Self {
+        Self { args }
+    }
+}
+
+#[async_trait]
+impl<'a> Execute for TunnelCommand<'a> {
+    async fn execute(&self, global_args: &Args) -> Result<(), Error> {
+        let output = global_args.output();
+        let client = Client::new();
+
+        output.message(format!(
+            "Forwarding webhooks from {} to {}",
+            self.args.source(),
+            self.args.to()
+        ));
+
+        // Keep the tunnel open until the user stops the command
+        loop {
+            if let Err(error) = relay(&client, self.args.source(), self.args.to(), output).await {
+                eprintln!("Lost connection to {}: {error:#}", self.args.source());
+            }
+
+            sleep(RECONNECT_DELAY).await;
+        }
+    }
+}
+
+/// Relay the webhooks from a channel to a local server
+///
+/// This function connects to the channel and forwards every webhook that is streamed to it until
+/// the channel closes the connection. Events other than `message` are used by smee.io to signal
+/// that the connection is ready or still alive, and are ignored. Messages that cannot be parsed are
+/// reported and skipped, so that a single malformed event does not close the relay. With `--json`,
+/// every forwarded webhook is reported as a line of JSON.
+pub async fn relay(
+    client: &Client,
+    source: &Url,
+    target: &Url,
+    output: Output,
+) -> Result<(), Error> {
+    let mut response = client
+        .get(source.clone())
+        .header(ACCEPT, "text/event-stream")
+        .header("User-Agent", "otterbuild/github-dev-app")
+        .send()
+        .await
+        .context("failed to connect to channel")?
+        .error_for_status()
+        .context("failed to subscribe to channel")?;
+
+    let mut parser = EventStreamParser::default();
+
+    while let Some(chunk) = response
+        .chunk()
+        .await
+        .context("failed to read from channel")?
+    {
+        for event in parser.feed(&chunk) {
+            if event.event() != "message" {
+                continue;
+            }
+
+            let delivery = match Delivery::from_event_data(event.data()) {
+                Ok(delivery) => delivery,
+                Err(error) => {
+                    eprintln!("skipping webhook: {error:#}");
+                    output.event(&json!({ "error": format!("{error:#}") }))?;
+                    continue;
+                }
+            };
+            let event_name = delivery.event().unwrap_or("unknown").to_string();
+
+            match delivery.forward(client, target).await {
+                Ok(status) => {
+                    output.message(format!("{event_name} -> {target} ({status})"));
+                    output.event(&json!({
+                        "event": event_name,
+                        "target": target,
+                        "status": status.as_u16(),
+                    }))?;
+                }
+                Err(error) => {
+                    eprintln!("{event_name} -> {target} failed: {error:#}");
+                    output.event(&json!({
+                        "event": event_name,
+                        "target": target,
+                        "error": format!("{error:#}"),
+                    }))?;
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::header::CONTENT_TYPE;
+    use axum::routing::get;
+    use axum::Router;
+    use indoc::indoc;
+    use mockito::Server;
+    use tokio::net::TcpListener;
+
+    use super::*;
+
+    /// Start a local stand-in for smee.io that streams the given events and closes the connection
+    async fn start_channel(events: &'static str) -> Url {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        let app = Router::new().route(
+            "/channel",
+            get(move || async move { ([(CONTENT_TYPE, "text/event-stream")], events) }),
+        );
+        tokio::spawn(async move { axum::serve(listener, app).await });
+
+        Url::parse(&format!("http://{addr}/channel")).unwrap()
+    }
+
+    #[tokio::test]
+    async fn relay_forwards_messages() {
+        let source = start_channel(indoc! {r#"
+            event: ready
+            data: {}
+
+            event: ping
+            data: {}
+
+            data: {"x-github-event":"push","body":{"ref":"refs/heads/main"}}
+
+        "#})
+        .await;
+
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("POST", "/webhooks")
+            .match_header("x-github-event", "push")
+            .match_body(r#"{"ref":"refs/heads/main"}"#)
+            .expect(1)
+            .create_async()
+            .await;
+        let target = Url::parse(&format!("{}/webhooks", server.url())).unwrap();
+
+        relay(&Client::new(), &source, &target, Output::default())
+            .await
+            .unwrap();
+
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn relay_skips_malformed_messages() {
+        let source = start_channel(indoc! {r#"
+            data: ping
+
+            data: {"x-github-event":"push","body":{"ref":"refs/heads/main"}}
+
+        "#})
+        .await;
+
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("POST", "/webhooks")
+            .match_header("x-github-event", "push")
+            .expect(1)
+            .create_async()
+            .await;
+        let target = Url::parse(&format!("{}/webhooks", server.url())).unwrap();
+
+        relay(&Client::new(), &source, &target, Output::default())
+            .await
+            .unwrap();
+
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn relay_errors_on_unreachable_channel() {
+        let source = Url::parse("http://127.0.0.1:1/channel").unwrap();
+        let target = Url::parse("http://127.0.0.1:1/webhooks").unwrap();
+
+        let error = relay(&Client::new(), &source, &target, Output::default())
+            .await
+            .unwrap_err();
+
+        assert_eq!("failed to connect to channel", error.to_string());
+    }
+
+    #[test]
+    fn trait_send() {
+        fn assert_send<T: Send>() {}
+        assert_send::<TunnelCommand>();
+    }
+
+    #[test]
+    fn trait_sync() {
+        fn assert_sync<T: Sync>() {}
+        assert_sync::<TunnelCommand>();
+    }
+
+    #[test]
+    fn trait_unpin() {
+        fn assert_unpin<T: Unpin>() {}
+        assert_unpin::<TunnelCommand>();
+    }
+}
@@ -0,0 +1,203 @@
+//! Webhook deliveries received from a smee.io channel
+
+use std::collections::BTreeMap;
+
+use anyhow::{Context, Error};
+use getset::Getters;
+use reqwest::{Client, StatusCode};
+use serde_json::value::RawValue;
+use url::Url;
+
+/// Headers that are recorded by smee.io but must not be replayed to the local server
+///
+/// These headers describe the connection between GitHub and smee.io, and are set again by the HTTP
+/// client when the delivery is forwarded. The `timestamp` is added to every delivery by smee.io.
+const SKIPPED_HEADERS: [&str; 6] = [
+    "accept-encoding",
+    "connection",
+    "content-length",
+    "host",
+    "timestamp",
+    "transfer-encoding",
+];
+
+/// A webhook delivery received from a smee.io channel
+///
+/// smee.io serializes each webhook it receives into a single JSON object. The request body is
+/// stored under the key `body`, the query parameters under `query`, and every other key is one of
+/// the original request headers.
+#[derive(Clone, Debug, Getters)]
+pub struct Delivery {
+    /// The original headers of the webhook
+    #[getset(get = "pub")]
+    headers: BTreeMap<String, String>,
+
+    /// The query parameters of the webhook
+    #[getset(get = "pub")]
+    query: BTreeMap<String, String>,
+
+    /// The body of the webhook, exactly as it was serialized by smee.io
+    body: Option<Box<RawValue>>,
+}
+
+impl Delivery {
+    /// Parse a delivery from the data of a server-sent event
+    ///
+    /// The body is kept as raw JSON so that it can be forwarded byte by byte. Re-serializing it
+    /// would reorder the keys and break the verification of the webhook signature.
+    pub fn from_event_data(data: &str) -> Result<Self, Error> {
+        let mut fields: BTreeMap<String, Box<RawValue>> =
+            serde_json::from_str(data).context("failed to parse webhook delivery")?;
+
+        let body = fields.remove("body");
+        let query = match fields.remove("query") {
+            Some(query) => serde_json::from_str::<BTreeMap<String, Box<RawValue>>>(query.get())
+                .context("failed to parse query of webhook delivery")?
+                .into_iter()
+                .map(|(key, value)| (key, raw_to_string(&value)))
+                .collect(),
+            None => BTreeMap::new(),
+        };
+
+        let headers = fields
+            .into_iter()
+            .filter(|(key, _)| !SKIPPED_HEADERS.contains(&key.to_lowercase().as_str()))
+            .map(|(key, value)| (key, raw_to_string(&value)))
+            .collect();
+
+        Ok(Self {
+            headers,
+            query,
+            body,
+        })
+    }
+
+    /// Return the name of the GitHub event, if the delivery is a GitHub webhook
+    pub fn event(&self) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case("x-github-event"))
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Forward the delivery to the local server
+    ///
+    /// The delivery is sent as a `POST` request to the target. Its query parameters are merged into
+    /// the query of the target, and the headers and body are replayed unchanged.
+    pub async fn forward(&self, client: &Client, target: &Url) -> Result<StatusCode, Error> {
+        let mut url = target.clone();
+        if !self.query.is_empty() {
+            let mut query: BTreeMap<String, String> = target.query_pairs().into_owned().collect();
+            query.extend(self.query.clone());
+
+            url.query_pairs_mut().clear().extend_pairs(query);
+        }
+
+        let mut request = client.post(url);
+        for (key, value) in &self.headers {
+            request = request.header(key, value);
+        }
+        if let Some(body) = &self.body {
+            request = request.body(body.get().to_string());
+        }
+
+        let response = request
+            .send()
+            .await
+            .context("failed to forward webhook to local server")?;
+
+        Ok(response.status())
+    }
+}
+
+/// Convert a raw JSON value into the string that is sent over the wire
+///
+/// Strings are unquoted, while all other values are forwarded with their JSON representation.
+fn raw_to_string(value: &RawValue) -> String {
+    serde_json::from_str::<String>(value.get()).unwrap_or_else(|_| value.get().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+    use mockito::{Matcher, Server};
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    const DATA: &str = indoc! {r#"
+        {
+            "host": "smee.io",
+            "x-github-event": "issues",
+            "x-hub-signature-256": "sha256=abc",
+            "content-type": "application/json",
+            "timestamp": 1718000000000,
+            "query": {"source": "github"},
+            "body": {"zebra": 1, "action": "opened"}
+        }
+    "#};
+
+    #[test]
+    fn from_event_data_keeps_relevant_headers() {
+        let delivery = Delivery::from_event_data(DATA).unwrap();
+
+        let headers: Vec<&str> = delivery.headers().keys().map(String::as_str).collect();
+
+        assert_eq!(
+            vec!["content-type", "x-github-event", "x-hub-signature-256"],
+            headers
+        );
+        assert_eq!(Some("issues"), delivery.event());
+    }
+
+    #[test]
+    fn from_event_data_errors_on_invalid_json() {
+        let error = Delivery::from_event_data("ping").unwrap_err();
+
+        assert_eq!("failed to parse webhook delivery", error.to_string());
+    }
+
+    #[tokio::test]
+    async fn forward_replays_delivery() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("POST", "/webhooks")
+            .match_query(Matcher::AllOf(vec![
+                Matcher::UrlEncoded("source".into(), "github".into()),
+                Matcher::UrlEncoded("token".into(), "otter".into()),
+            ]))
+            .match_header("x-github-event", "issues")
+            .match_header("x-hub-signature-256", "sha256=abc")
+            .match_header("host", Matcher::Regex("127.0.0.1".into()))
+            .match_body(r#"{"zebra": 1, "action": "opened"}"#)
+            .with_status(202)
+            .create_async()
+            .await;
+
+        let target = Url::parse(&format!("{}/webhooks?token=otter", server.url())).unwrap();
+        let delivery = Delivery::from_event_data(DATA).unwrap();
+
+        let status = delivery.forward(&Client::new(), &target).await.unwrap();
+
+        mock.assert_async().await;
+        assert_eq!(StatusCode::ACCEPTED, status);
+    }
+
+    #[test]
+    fn trait_send() {
+        fn assert_send<T: Send>() {}
+        assert_send::<Delivery>();
+    }
+
+    #[test]
+    fn trait_sync() {
+        fn assert_sync<T: Sync>() {}
+        assert_sync::<Delivery>();
+    }
+
+    #[test]
+    fn trait_unpin() {
+        fn assert_unpin<T: Unpin>() {}
+        assert_unpin::<Delivery>();
+    }
+}
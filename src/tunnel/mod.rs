@@ -0,0 +1,9 @@
+//! Forward webhooks from a smee.io channel to a local server
+
+pub use self::args::*;
+pub use self::command::*;
+
+mod args;
+mod command;
+mod delivery;
+mod sse;
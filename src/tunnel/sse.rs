@@ -0,0 +1,190 @@
+//! Parser for streams of Server-Sent Events
+
+use getset::Getters;
+
+/// The event type that is used when the stream does not specify one
+const DEFAULT_EVENT_TYPE: &str = "message";
+
+/// An event received from a Server-Sent Events stream
+///
+/// Server-Sent Events consist of an optional event type and a data payload. Events without an
+/// explicit type are of the type `message`.
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Getters)]
+pub struct ServerSentEvent {
+    /// The type of the event
+    #[getset(get = "pub")]
+    event: String,
+
+    /// The data payload of the event
+    #[getset(get = "pub")]
+    data: String,
+}
+
+/// Incremental parser for a stream of Server-Sent Events
+///
+/// The parser implements the event stream format of the HTML specification. It is fed the chunks
+/// of the response body as they arrive over the network, and returns the events that have been
+/// completed by each chunk. Lines and events can be split across any number of chunks.
+#[derive(Clone, Eq, PartialEq, Debug, Default)]
+pub struct EventStreamParser {
+    /// The bytes of the line that is currently being read
+    line: Vec<u8>,
+
+    /// Whether a line feed must be skipped because the last line ended with a carriage return
+    skip_line_feed: bool,
+
+    /// The type of the event that is currently being read
+    event: Option<String>,
+
+    /// The data of the event that is currently being read
+    data: Option<String>,
+}
+
+impl EventStreamParser {
+    /// Feed a chunk of the stream to the parser
+    ///
+    /// This method returns all events that were completed by the chunk. Incomplete events are
+    /// buffered until the next chunk arrives.
+    pub fn feed(&mut self, chunk: &[u8]) -> Vec<ServerSentEvent> {
+        let mut events = Vec::new();
+
+        for &byte in chunk {
+            // Lines can end with `\r\n`, which must not be treated as two line breaks
+            if std::mem::take(&mut self.skip_line_feed) && byte == b'\n' {
+                continue;
+            }
+
+            match byte {
+                b'\r' | b'\n' => {
+                    self.skip_line_feed = byte == b'\r';
+
+                    let line = String::from_utf8_lossy(&self.line).into_owned();
+                    self.line.clear();
+
+                    if let Some(event) = self.process_line(&line) {
+                        events.push(event);
+                    }
+                }
+                _ => self.line.push(byte),
+            }
+        }
+
+        events
+    }
+
+    /// Process a single line of the stream
+    ///
+    /// Empty lines dispatch the event that has been read so far, lines starting with a colon are
+    /// comments, and all other lines are fields of the current event.
+    fn process_line(&mut self, line: &str) -> Option<ServerSentEvent> {
+        if line.is_empty() {
+            return self.dispatch();
+        }
+
+        if line.starts_with(':') {
+            return None;
+        }
+
+        let (field, value) = match line.split_once(':') {
+            Some((field, value)) => (field, value.strip_prefix(' ').unwrap_or(value)),
+            None => (line, ""),
+        };
+
+        match field {
+            "event" => self.event = Some(value.into()),
+            "data" => match &mut self.data {
+                Some(data) => {
+                    data.push('\n');
+                    data.push_str(value);
+                }
+                None => self.data = Some(value.into()),
+            },
+            // The fields `id` and `retry` are not needed to forward webhooks
+            _ => {}
+        }
+
+        None
+    }
+
+    /// Dispatch the event that has been read so far
+    ///
+    /// Events without any data are discarded, as required by the specification.
+    fn dispatch(&mut self) -> Option<ServerSentEvent> {
+        let event = self.event.take();
+        let data = self.data.take()?;
+
+        Some(ServerSentEvent {
+            event: event
+                .filter(|event| !event.is_empty())
+                .unwrap_or_else(|| DEFAULT_EVENT_TYPE.into()),
+            data,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn feed_parses_events() {
+        let mut parser = EventStreamParser::default();
+
+        let events = parser.feed(b"event: ready\ndata: {}\n\ndata: {\"a\":1}\n\n");
+
+        assert_eq!(2, events.len());
+        assert_eq!("ready", events[0].event());
+        assert_eq!("message", events[1].event());
+        assert_eq!("{\"a\":1}", events[1].data());
+    }
+
+    #[test]
+    fn feed_buffers_events_across_chunks() {
+        let mut parser = EventStreamParser::default();
+
+        assert!(parser.feed(b"data: hel").is_empty());
+        assert!(parser.feed(b"lo\r").is_empty());
+        let events = parser.feed(b"\n\r\n");
+
+        assert_eq!(1, events.len());
+        assert_eq!("hello", events[0].data());
+    }
+
+    #[test]
+    fn feed_joins_multiple_data_lines() {
+        let mut parser = EventStreamParser::default();
+
+        let events = parser.feed(b"data: first\ndata:second\n\n");
+
+        assert_eq!("first\nsecond", events[0].data());
+    }
+
+    #[test]
+    fn feed_ignores_comments_and_empty_events() {
+        let mut parser = EventStreamParser::default();
+
+        let events = parser.feed(b": keep-alive\n\nevent: ping\n\n");
+
+        assert!(events.is_empty());
+    }
+
+    #[test]
+    fn trait_send() {
+        fn assert_send<T: Send>() {}
+        assert_send::<EventStreamParser>();
+    }
+
+    #[test]
+    fn trait_sync() {
+        fn assert_sync<T: Sync>() {}
+        assert_sync::<EventStreamParser>();
+    }
+
+    #[test]
+    fn trait_unpin() {
+        fn assert_unpin<T: Unpin>() {}
+        assert_unpin::<EventStreamParser>();
+    }
+}
@@ -0,0 +1,63 @@
+use std::process::Command;
+
+use anyhow::Error;
+use assert_cmd::prelude::*;
+use indoc::formatdoc;
+use mockito::{Matcher, Server};
+use tempfile::tempdir;
+
+const PRIVATE_KEY: &str = include_str!("fixtures/private-key.pem");
+
+#[tokio::test]
+async fn sends_request_as_installation() -> Result<(), Error> {
+    let mut command = Command::cargo_bin("github-dev-app")?;
+
+    // Execute command in a temporary directory with the app's secrets
+    let temp_dir = tempdir()?;
+    command.current_dir(temp_dir.path());
+    std::fs::write(
+        temp_dir.path().join(".env"),
+        formatdoc! {r#"
+            GITHUB_APP_ID=1
+            GITHUB_APP_NAME=github-dev-app
+            GITHUB_CLIENT_ID="Iv1.8a61f9b3a7aba766"
+            GITHUB_CLIENT_SECRET=1726be1638095a19edd134c77bde3aa2ece1e5d8
+            GITHUB_PRIVATE_KEY="{}"
+        "#, PRIVATE_KEY.escape_default()},
+    )?;
+
+    // Mock GitHub's API
+    let mut server = Server::new_async().await;
+    let token = server
+        .mock("POST", "/app/installations/42/access_tokens")
+        .match_header("authorization", Matcher::Regex("^Bearer ey".into()))
+        .with_status(201)
+        .with_body(r#"{"token":"ghs_otters"}"#)
+        .create_async()
+        .await;
+    let repositories = server
+        .mock("GET", "/installation/repositories")
+        .match_header("authorization", "Bearer ghs_otters")
+        .with_body(r#"{"total_count":1,"repositories":[{"full_name":"otterbuild/otters"}]}"#)
+        .create_async()
+        .await;
+
+    command
+        .arg("api")
+        .arg("get")
+        .arg("/installation/repositories")
+        .arg("--installation")
+        .arg("42")
+        .arg("--github")
+        .arg(server.url())
+        .assert()
+        .success()
+        .stdout(predicates::str::contains(
+            r#""full_name": "otterbuild/otters""#,
+        ));
+
+    token.assert_async().await;
+    repositories.assert_async().await;
+
+    Ok(())
+}
@@ -0,0 +1,152 @@
+use std::path::Path;
+use std::process::Command;
+
+use anyhow::Error;
+use assert_cmd::prelude::*;
+use tempfile::tempdir;
+
+fn command(directory: &Path, config_home: &Path) -> Result<Command, Error> {
+    let mut command = Command::cargo_bin("github-dev-app")?;
+
+    command
+        .current_dir(directory)
+        .env("CI", "true")
+        .env("XDG_CONFIG_HOME", config_home)
+        .env_remove("APPDATA");
+
+    Ok(command)
+}
+
+#[test]
+fn reads_options_from_project_config_in_parent_directory() -> Result<(), Error> {
+    let temp_dir = tempdir()?;
+    std::fs::create_dir_all(temp_dir.path().join("github"))?;
+    std::fs::create_dir_all(temp_dir.path().join("crates/server"))?;
+    std::fs::write(
+        temp_dir.path().join("github/app.json"),
+        r#"{"url":"http://localhost"}"#,
+    )?;
+    std::fs::write(
+        temp_dir.path().join(".github-dev-app.toml"),
+        "manifest = \"github/app.json\"\n\n[register]\ntimeout = \"1s\"\n",
+    )?;
+
+    // The command times out instead of failing to read the manifest
+    command(&temp_dir.path().join("crates/server"), temp_dir.path())?
+        .arg("register")
+        .assert()
+        .code(9);
+
+    Ok(())
+}
+
+#[test]
+fn reads_options_from_user_config() -> Result<(), Error> {
+    let temp_dir = tempdir()?;
+    let config_home = temp_dir.path().join("config");
+    std::fs::create_dir_all(config_home.join("github-dev-app"))?;
+    std::fs::write(
+        config_home.join("github-dev-app/config.toml"),
+        "timeout = \"1s\"\n",
+    )?;
+    std::fs::write(
+        temp_dir.path().join("manifest.json"),
+        r#"{"url":"http://localhost"}"#,
+    )?;
+
+    command(temp_dir.path(), &config_home)?
+        .arg("register")
+        .arg("manifest.json")
+        .assert()
+        .code(9);
+
+    Ok(())
+}
+
+#[test]
+fn command_line_takes_precedence_over_config() -> Result<(), Error> {
+    let temp_dir = tempdir()?;
+    std::fs::write(
+        temp_dir.path().join("manifest.json"),
+        r#"{"url":"http://localhost"}"#,
+    )?;
+    std::fs::write(
+        temp_dir.path().join(".github-dev-app.toml"),
+        "manifest = \"missing.json\"\ntimeout = \"10m\"\n",
+    )?;
+
+    command(temp_dir.path(), temp_dir.path())?
+        .arg("register")
+        .arg("manifest.json")
+        .arg("--timeout")
+        .arg("1s")
+        .assert()
+        .code(9);
+
+    Ok(())
+}
+
+#[test]
+fn fails_with_hint_for_unknown_option() -> Result<(), Error> {
+    let temp_dir = tempdir()?;
+    std::fs::write(
+        temp_dir.path().join(".github-dev-app.toml"),
+        "prot = 64001\n",
+    )?;
+
+    command(temp_dir.path(), temp_dir.path())?
+        .arg("register")
+        .assert()
+        .code(10)
+        .stderr(predicates::str::contains("unknown option `prot`"))
+        .stderr(predicates::str::contains(
+            "Options are named like the command-line flags",
+        ));
+
+    Ok(())
+}
+
+#[test]
+fn prints_help_despite_invalid_config() -> Result<(), Error> {
+    let temp_dir = tempdir()?;
+    std::fs::write(
+        temp_dir.path().join(".github-dev-app.toml"),
+        "prot = 64001\n",
+    )?;
+
+    command(temp_dir.path(), temp_dir.path())?
+        .arg("register")
+        .arg("--help")
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("Usage:"))
+        .stderr(predicates::str::contains(
+            "Warning: ignoring the configuration files",
+        ));
+
+    Ok(())
+}
+
+#[test]
+fn doctor_reports_invalid_config() -> Result<(), Error> {
+    let temp_dir = tempdir()?;
+    std::fs::write(
+        temp_dir.path().join(".github-dev-app.toml"),
+        "prot = 64001\n",
+    )?;
+
+    command(temp_dir.path(), temp_dir.path())?
+        .arg("doctor")
+        .arg("--github")
+        .arg("http://127.0.0.1:1")
+        .arg("--retries")
+        .arg("0")
+        .assert()
+        .code(11)
+        .stdout(predicates::str::contains(
+            "[failed] config: invalid configuration in",
+        ))
+        .stdout(predicates::str::contains("unknown option `prot`"));
+
+    Ok(())
+}
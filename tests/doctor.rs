@@ -0,0 +1,125 @@
+use std::process::Command;
+
+use anyhow::Error;
+use assert_cmd::prelude::*;
+use indoc::formatdoc;
+use mockito::Server;
+use tempfile::tempdir;
+
+const PRIVATE_KEY: &str = include_str!("fixtures/private-key.pem");
+
+#[tokio::test]
+async fn reports_problems_with_hints() -> Result<(), Error> {
+    let mut command = Command::cargo_bin("github-dev-app")?;
+
+    // Execute command in a git repository that does not ignore the .env file
+    let temp_dir = tempdir()?;
+    command.current_dir(temp_dir.path());
+    Command::new("git")
+        .arg("init")
+        .arg("--quiet")
+        .current_dir(temp_dir.path())
+        .status()?;
+    std::fs::write(
+        temp_dir.path().join(".env"),
+        formatdoc! {r#"
+            GITHUB_APP_ID=1
+            GITHUB_APP_NAME=github-dev-app
+            GITHUB_CLIENT_ID="Iv1.8a61f9b3a7aba766"
+            GITHUB_CLIENT_SECRET=1726be1638095a19edd134c77bde3aa2ece1e5d8
+            GITHUB_PRIVATE_KEY="{}"
+        "#, PRIVATE_KEY.escape_default()},
+    )?;
+
+    // Mock GitHub's API
+    let mut server = Server::new_async().await;
+    let _app = server
+        .mock("GET", "/app")
+        .with_body(r#"{"id": 1, "slug": "github-dev-app"}"#)
+        .create_async()
+        .await;
+    let _root = server.mock("GET", "/").with_body("{}").create_async().await;
+
+    command
+        .arg("doctor")
+        .arg("--github")
+        .arg(server.url())
+        .assert()
+        .code(11)
+        .stdout(predicates::str::contains(
+            "[ok] authentication: GitHub accepted the token for the app github-dev-app",
+        ))
+        .stdout(predicates::str::contains(
+            "[skipped] webhook-secret: GITHUB_WEBHOOK_SECRET is not set",
+        ))
+        .stdout(predicates::str::contains(
+            "[failed] gitignore: .env is not ignored by git",
+        ))
+        .stdout(predicates::str::contains(
+            "Hint: Add `.env` to the .gitignore file of the repository",
+        ))
+        .stderr(predicates::str::contains(
+            "Error: 1 of 8 checks failed: gitignore",
+        ));
+
+    Ok(())
+}
+
+#[test]
+fn includes_checks_in_json_error() -> Result<(), Error> {
+    let mut command = Command::cargo_bin("github-dev-app")?;
+
+    let temp_dir = tempdir()?;
+    command.current_dir(temp_dir.path());
+
+    let output = command
+        .arg("--json")
+        .arg("doctor")
+        .arg("--github")
+        .arg("http://127.0.0.1:1")
+        .arg("--retries")
+        .arg("0")
+        .output()?;
+
+    let result: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    let checks = result["error"]["details"]["checks"]
+        .as_array()
+        .cloned()
+        .unwrap_or_default();
+
+    assert_eq!(Some(11), output.status.code());
+    assert_eq!(8, checks.len());
+    assert_eq!(serde_json::json!("env-file"), checks[1]["name"]);
+    assert_eq!(serde_json::json!("failed"), checks[1]["status"]);
+    assert_eq!(
+        serde_json::json!(".env does not exist"),
+        checks[1]["message"]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn fails_without_env_file() -> Result<(), Error> {
+    let mut command = Command::cargo_bin("github-dev-app")?;
+
+    let temp_dir = tempdir()?;
+    command.current_dir(temp_dir.path());
+
+    command
+        .arg("doctor")
+        .arg("--github")
+        .arg("http://127.0.0.1:1")
+        .arg("--retries")
+        .arg("0")
+        .assert()
+        .code(11)
+        .stdout(predicates::str::contains(
+            "[failed] env-file: .env does not exist",
+        ))
+        .stdout(predicates::str::contains(
+            "[skipped] authentication: requires a valid private key",
+        ));
+
+    Ok(())
+}
@@ -0,0 +1,69 @@
+use std::process::Command;
+
+use anyhow::Error;
+use assert_cmd::prelude::*;
+use indoc::indoc;
+use mockito::Server;
+use pretty_assertions::assert_eq;
+use tempfile::tempdir;
+
+#[tokio::test]
+async fn writes_manifest_of_public_app() -> Result<(), Error> {
+    let mut command = Command::cargo_bin("github-dev-app")?;
+
+    // Execute command in a temporary directory without credentials
+    let temp_dir = tempdir()?;
+    command.current_dir(temp_dir.path());
+
+    // Mock GitHub's API
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("GET", "/apps/octoapp")
+        .with_body(indoc! {r#"
+            {
+                "id": 1,
+                "slug": "octoapp",
+                "name": "Octoapp",
+                "description": "An app for otters",
+                "external_url": "https://example.com",
+                "html_url": "https://github.com/apps/octoapp",
+                "permissions": { "issues": "write" },
+                "events": ["issues"]
+            }
+        "#})
+        .create_async()
+        .await;
+
+    command
+        .arg("export-manifest")
+        .arg("--app")
+        .arg("octoapp")
+        .arg("--output")
+        .arg("manifest.json")
+        .arg("--github")
+        .arg(server.url())
+        .assert()
+        .success();
+
+    mock.assert_async().await;
+
+    let manifest = std::fs::read_to_string(temp_dir.path().join("manifest.json"))?;
+    assert_eq!(
+        indoc! {r#"
+            {
+              "name": "Octoapp",
+              "url": "https://example.com",
+              "description": "An app for otters",
+              "default_events": [
+                "issues"
+              ],
+              "default_permissions": {
+                "issues": "write"
+              }
+            }
+        "#},
+        manifest
+    );
+
+    Ok(())
+}
@@ -0,0 +1,24 @@
+use std::process::Command;
+
+use anyhow::Error;
+use assert_cmd::prelude::*;
+use tempfile::TempDir;
+
+#[test]
+fn init_refuses_to_overwrite_manifest() -> Result<(), Error> {
+    let mut command = Command::cargo_bin("github-dev-app")?;
+
+    let directory = TempDir::new()?;
+    std::fs::write(directory.path().join("manifest.json"), "{}")?;
+
+    command
+        .current_dir(directory.path())
+        .arg("init")
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains(
+            "manifest.json already exists, pass --force to overwrite it",
+        ));
+
+    Ok(())
+}
@@ -0,0 +1,79 @@
+use std::process::Command;
+
+use anyhow::Error;
+use assert_cmd::prelude::*;
+use indoc::{formatdoc, indoc};
+use mockito::{Matcher, Server};
+use tempfile::tempdir;
+
+const PRIVATE_KEY: &str = include_str!("fixtures/private-key.pem");
+
+#[tokio::test]
+async fn lists_installations() -> Result<(), Error> {
+    let mut command = Command::cargo_bin("github-dev-app")?;
+
+    // Execute command in a temporary directory with the app's secrets
+    let temp_dir = tempdir()?;
+    command.current_dir(temp_dir.path());
+    std::fs::write(
+        temp_dir.path().join(".env"),
+        formatdoc! {r#"
+            GITHUB_APP_ID=1
+            GITHUB_APP_NAME=github-dev-app
+            GITHUB_CLIENT_ID="Iv1.8a61f9b3a7aba766"
+            GITHUB_CLIENT_SECRET=1726be1638095a19edd134c77bde3aa2ece1e5d8
+            GITHUB_PRIVATE_KEY="{}"
+        "#, PRIVATE_KEY.escape_default()},
+    )?;
+
+    // Mock GitHub's API
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("GET", "/app/installations?per_page=100")
+        .match_header("authorization", Matcher::Regex("^Bearer ey".into()))
+        .with_body(indoc! {r#"
+            [
+                {
+                    "id": 42,
+                    "account": { "login": "otterbuild" },
+                    "target_type": "Organization",
+                    "repository_selection": "all",
+                    "suspended_at": null
+                }
+            ]
+        "#})
+        .create_async()
+        .await;
+
+    command
+        .arg("installations")
+        .arg("list")
+        .arg("--github")
+        .arg(server.url())
+        .assert()
+        .success()
+        .stdout(predicates::str::contains(
+            "42  otterbuild  Organization  all           no",
+        ));
+
+    mock.assert_async().await;
+
+    Ok(())
+}
+
+#[test]
+fn errors_without_env_file() -> Result<(), Error> {
+    let mut command = Command::cargo_bin("github-dev-app")?;
+
+    let temp_dir = tempdir()?;
+    command.current_dir(temp_dir.path());
+
+    command
+        .arg("installations")
+        .arg("list")
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("failed to read .env"));
+
+    Ok(())
+}
@@ -0,0 +1,181 @@
+use std::process::Command;
+
+use anyhow::Error;
+use assert_cmd::prelude::*;
+use indoc::indoc;
+use tempfile::{NamedTempFile, TempDir};
+
+#[test]
+fn check_accepts_valid_manifest() -> Result<(), Error> {
+    let mut command = Command::cargo_bin("github-dev-app")?;
+
+    let manifest = NamedTempFile::new()?;
+    std::fs::write(manifest.path(), r#"{"url":"http://localhost"}"#)?;
+
+    command
+        .arg("manifest")
+        .arg("check")
+        .arg(manifest.path())
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("is a valid manifest"));
+
+    Ok(())
+}
+
+#[test]
+fn check_reports_problems_with_positions() -> Result<(), Error> {
+    let mut command = Command::cargo_bin("github-dev-app")?;
+
+    let manifest = NamedTempFile::new()?;
+    std::fs::write(
+        manifest.path(),
+        indoc! {r#"
+            {
+              "url": "http://localhost",
+              "default_permissions": { "isues": "write" }
+            }
+        "#},
+    )?;
+
+    command
+        .arg("manifest")
+        .arg("check")
+        .arg(manifest.path())
+        .assert()
+        .failure()
+        .stdout(predicates::str::contains(
+            ":3:28: unknown permission `isues`",
+        ))
+        .stderr(predicates::str::contains("found 1 problem(s)"));
+
+    Ok(())
+}
+
+#[test]
+fn check_merges_extended_manifest() -> Result<(), Error> {
+    let mut command = Command::cargo_bin("github-dev-app")?;
+
+    let directory = TempDir::new()?;
+    std::fs::write(
+        directory.path().join("base.json"),
+        r#"{"url":"http://localhost"}"#,
+    )?;
+    std::fs::write(
+        directory.path().join("child.json"),
+        r#"{"extends":"base.json","name":"x"}"#,
+    )?;
+
+    command
+        .arg("manifest")
+        .arg("check")
+        .arg(directory.path().join("child.json"))
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("is a valid manifest"));
+
+    Ok(())
+}
+
+#[test]
+fn check_reads_manifest_from_cargo_toml() -> Result<(), Error> {
+    let mut command = Command::cargo_bin("github-dev-app")?;
+
+    let directory = TempDir::new()?;
+    std::fs::write(
+        directory.path().join("Cargo.toml"),
+        indoc! {r#"
+            [package]
+            name = "octoapp"
+
+            [package.metadata.github-app]
+            url = "http://localhost"
+        "#},
+    )?;
+
+    command
+        .current_dir(directory.path())
+        .arg("manifest")
+        .arg("check")
+        .assert()
+        .success()
+        .stdout(predicates::str::contains(
+            "Cargo.toml[package.metadata.github-app] is a valid manifest",
+        ));
+
+    Ok(())
+}
+
+#[test]
+fn schema_prints_json_schema() -> Result<(), Error> {
+    let mut command = Command::cargo_bin("github-dev-app")?;
+
+    let output = command.arg("manifest").arg("schema").output()?;
+    let schema: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+
+    assert!(output.status.success());
+    assert_eq!("GitHub App manifest", schema["title"]);
+
+    Ok(())
+}
+
+#[test]
+fn check_prints_json_result() -> Result<(), Error> {
+    let mut command = Command::cargo_bin("github-dev-app")?;
+
+    let manifest = NamedTempFile::new()?;
+    std::fs::write(manifest.path(), r#"{"url":"http://localhost"}"#)?;
+
+    let output = command
+        .arg("--json")
+        .arg("manifest")
+        .arg("check")
+        .arg(manifest.path())
+        .output()?;
+
+    let result: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+
+    assert!(output.status.success());
+    assert_eq!(serde_json::json!(true), result["valid"]);
+    assert!(String::from_utf8(output.stderr)?.contains("is a valid manifest"));
+
+    Ok(())
+}
+
+#[test]
+fn check_prints_json_diagnostics() -> Result<(), Error> {
+    let mut command = Command::cargo_bin("github-dev-app")?;
+
+    let manifest = NamedTempFile::new()?;
+    std::fs::write(
+        manifest.path(),
+        indoc! {r#"
+            {
+              "url": "http://localhost",
+              "default_permissions": { "isues": "write" }
+            }
+        "#},
+    )?;
+
+    let output = command
+        .arg("--json")
+        .arg("manifest")
+        .arg("check")
+        .arg(manifest.path())
+        .output()?;
+
+    let result: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+
+    assert_eq!(Some(5), output.status.code());
+    assert_eq!(
+        serde_json::json!([{
+            "line": 3,
+            "column": 28,
+            "message": "unknown permission `isues`",
+            "severity": "error",
+        }]),
+        result["error"]["details"]["diagnostics"]
+    );
+
+    Ok(())
+}
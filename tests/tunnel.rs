@@ -0,0 +1,73 @@
+use std::process::Command;
+use std::time::Duration;
+
+use anyhow::Error;
+use assert_cmd::prelude::*;
+use axum::http::header::CONTENT_TYPE;
+use axum::routing::get;
+use axum::Router;
+use indoc::indoc;
+use mockito::Server;
+use tokio::net::TcpListener;
+use tokio::time::sleep;
+
+const EVENTS: &str = indoc! {r#"
+    event: ready
+    data: {}
+
+    data: {"x-github-event":"issues","x-hub-signature-256":"sha256=otters","body":{"action":"opened"}}
+
+"#};
+
+#[tokio::test]
+async fn forwards_webhooks_from_channel() -> Result<(), Error> {
+    let mut command = Command::cargo_bin("github-dev-app")?;
+
+    // Start a local stand-in for smee.io
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let channel_url = format!("http://{}/channel", listener.local_addr()?);
+    let app = Router::new().route(
+        "/channel",
+        get(|| async { ([(CONTENT_TYPE, "text/event-stream")], EVENTS) }),
+    );
+    tokio::spawn(async move { axum::serve(listener, app).await });
+
+    // Mock the local webhook handler
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("POST", "/webhooks")
+        .match_header("x-github-event", "issues")
+        .match_header("x-hub-signature-256", "sha256=otters")
+        .match_body(r#"{"action":"opened"}"#)
+        // The tunnel reconnects and replays the channel after it has been closed
+        .expect_at_least(1)
+        .create_async()
+        .await;
+
+    // Execute the tunnel command
+    let mut process_handle = command
+        .arg("tunnel")
+        .arg("--source")
+        .arg(channel_url)
+        .arg("--to")
+        .arg(format!("{}/webhooks", server.url()))
+        .spawn()
+        .expect("failed to execute command");
+
+    // Wait for the webhook to be forwarded
+    for _ in 0..10 {
+        if mock.matched_async().await {
+            break;
+        } else {
+            sleep(Duration::from_millis(500)).await;
+        }
+    }
+
+    process_handle.kill().expect("failed to stop command");
+    process_handle.wait().expect("failed to wait for command");
+
+    // Assert that the webhook was forwarded
+    mock.assert_async().await;
+
+    Ok(())
+}